@@ -0,0 +1,118 @@
+//! Timing hooks for profiling compute cost from JS (`bench` feature).
+//!
+//! Buffers are allocated and filled inside WASM, so the measurement excludes
+//! the cost of copying typed arrays across the JS boundary.
+
+use wasm_bindgen::prelude::*;
+
+use crate::{flock_bounds, integrate_all, set_simd_enabled, simd_enabled};
+
+/// Time `iterations` calls of [`integrate_all`] over `count` boids.
+///
+/// Returns the elapsed time in milliseconds, measured with
+/// `performance.now()` in the browser or a worker. Buffer setup is not included.
+///
+/// # Arguments
+/// * `count` - Number of boids
+/// * `iterations` - Number of integration steps to run
+#[wasm_bindgen]
+pub fn bench_integrate(count: usize, iterations: u32) -> f64 {
+    let mut positions: Vec<f32> = (0..count * 2).map(|i| i as f32).collect();
+    let mut velocities: Vec<f32> = (0..count * 2)
+        .map(|i| if i % 2 == 0 { 1.0 } else { -0.5 })
+        .collect();
+    let accelerations = vec![0.01; count * 2];
+
+    let start = now_ms();
+    for _ in 0..iterations {
+        integrate_all(
+            &mut positions,
+            &mut velocities,
+            &accelerations,
+            1.0,
+            1.0,
+            4.0,
+            0.005,
+        );
+    }
+    let elapsed = now_ms() - start;
+
+    // Keep the work observable so it is not optimized away
+    core::hint::black_box(&positions);
+    elapsed.max(0.0)
+}
+
+/// Time `iterations` calls of [`flock_bounds`] over `count` boids, once
+/// with the SIMD path and once with the scalar loop.
+///
+/// Returns `[simd_ms, scalar_ms]`. The SIMD toggle is restored afterwards;
+/// in builds without `simd128` both runs take the scalar loop.
+///
+/// # Arguments
+/// * `count` - Number of boids
+/// * `iterations` - Number of reductions per path
+#[wasm_bindgen]
+pub fn bench_flock_bounds(count: usize, iterations: u32) -> Vec<f64> {
+    let positions: Vec<f32> = (0..count * 2).map(|i| (i * 37 % 1000) as f32).collect();
+    let mut bounds = [0.0; 4];
+    let was_enabled = simd_enabled();
+
+    let mut run = |enabled: bool| {
+        set_simd_enabled(enabled);
+        let start = now_ms();
+        for _ in 0..iterations {
+            flock_bounds(core::hint::black_box(&positions), &mut bounds);
+        }
+        let elapsed = now_ms() - start;
+        core::hint::black_box(&bounds);
+        elapsed.max(0.0)
+    };
+
+    let simd = run(true);
+    let scalar = run(false);
+    set_simd_enabled(was_enabled);
+    vec![simd, scalar]
+}
+
+/// Current high-resolution time in milliseconds.
+///
+/// Reads `performance` from the global scope, so it works on the main
+/// thread and in Web Workers alike. Returns 0 when the host has no
+/// `performance` object (e.g. a bare JS runtime).
+#[cfg(target_arch = "wasm32")]
+fn now_ms() -> f64 {
+    use wasm_bindgen::JsCast;
+
+    js_sys::Reflect::get(&js_sys::global(), &JsValue::from_str("performance"))
+        .ok()
+        .and_then(|p| p.dyn_into::<web_sys::Performance>().ok())
+        .map_or(0.0, |p| p.now())
+}
+
+/// Current time in milliseconds, for native test builds.
+#[cfg(not(target_arch = "wasm32"))]
+fn now_ms() -> f64 {
+    use std::sync::OnceLock;
+    use std::time::Instant;
+
+    static EPOCH: OnceLock<Instant> = OnceLock::new();
+    EPOCH.get_or_init(Instant::now).elapsed().as_secs_f64() * 1000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bench_integrate_smoke() {
+        let elapsed = bench_integrate(16, 10);
+        assert!(elapsed >= 0.0 && elapsed.is_finite());
+    }
+
+    #[test]
+    fn test_bench_flock_bounds_smoke() {
+        let timings = bench_flock_bounds(16, 10);
+        assert_eq!(timings.len(), 2);
+        assert!(timings.iter().all(|t| *t >= 0.0 && t.is_finite()));
+    }
+}
@@ -0,0 +1,346 @@
+//! Edge handling beyond plain wrap and bounce.
+
+use wasm_bindgen::prelude::*;
+
+use crate::{add_pair, bounce_axis, read_pair};
+
+/// Batch apply a soft inward force near the world edges.
+///
+/// The force ramps linearly from zero at the inner edge of the `margin` band
+/// to `strength` at the wall (and stays there outside the world). Corners get
+/// the sum of both axes.
+///
+/// # Arguments
+/// * `positions` - Array of positions (x, y pairs)
+/// * `out_accel` - Mutable array of accelerations to add into (x, y pairs)
+/// * `width` - World width
+/// * `height` - World height
+/// * `margin` - Width of the band along each edge
+/// * `strength` - Force magnitude at the wall
+#[wasm_bindgen]
+pub fn boundary_force_all(
+    positions: &[f32],
+    out_accel: &mut [f32],
+    width: f32,
+    height: f32,
+    margin: f32,
+    strength: f32,
+) {
+    if margin <= 0.0 {
+        return;
+    }
+
+    for i in 0..positions.len() / 2 {
+        let (px, py) = read_pair(positions, i);
+        add_pair(
+            out_accel,
+            i,
+            edge_push(px, width, margin) * strength,
+            edge_push(py, height, margin) * strength,
+        );
+    }
+}
+
+/// Signed inward push in [-1, 1] for coordinate `p` on an axis of `size`.
+pub(crate) fn edge_push(p: f32, size: f32, margin: f32) -> f32 {
+    let low = ((margin - p) / margin).clamp(0.0, 1.0);
+    let high = ((p - (size - margin)) / margin).clamp(0.0, 1.0);
+    low - high
+}
+
+/// Edge mode: leave the axis unbounded.
+pub const EDGE_NONE: u8 = 0;
+/// Edge mode: wrap around to the opposite side.
+pub const EDGE_WRAP: u8 = 1;
+/// Edge mode: reflect off the walls.
+pub const EDGE_BOUNCE: u8 = 2;
+/// Edge mode: steer back with a soft force near the walls.
+pub const EDGE_FORCE: u8 = 3;
+
+/// Batch apply edge handling with an independent mode per axis.
+///
+/// Modes are [`EDGE_NONE`] (0), [`EDGE_WRAP`] (1), [`EDGE_BOUNCE`] (2) and
+/// [`EDGE_FORCE`] (3); unknown values behave like `EDGE_NONE`. Wrapping and
+/// bouncing match [`wrap_positions_all`](crate::wrap_positions_all) and
+/// [`bounce_positions_all`](crate::bounce_positions_all) with full
+/// restitution. The force mode adds the [`boundary_force_all`] push for that
+/// axis into `out_accel` like the other force functions, so it is scaled by
+/// `dt` at integration; it is also the only mode that needs `margin` and
+/// `strength`, which the others ignore.
+///
+/// # Arguments
+/// * `positions` - Mutable array of positions (x, y pairs)
+/// * `velocities` - Mutable array of velocities (x, y pairs)
+/// * `out_accel` - Mutable array of accelerations to add into (x, y pairs)
+/// * `width` - World width
+/// * `height` - World height
+/// * `x_mode` - Edge mode for the x axis
+/// * `y_mode` - Edge mode for the y axis
+/// * `margin` - Width of the band along each edge (force mode only)
+/// * `strength` - Force magnitude at the wall (force mode only)
+#[wasm_bindgen]
+#[allow(clippy::too_many_arguments)]
+pub fn apply_bounds_all(
+    positions: &mut [f32],
+    velocities: &mut [f32],
+    out_accel: &mut [f32],
+    width: f32,
+    height: f32,
+    x_mode: u8,
+    y_mode: u8,
+    margin: f32,
+    strength: f32,
+) {
+    let count = (positions.len() / 2).min(velocities.len() / 2);
+    let axes = [(width, x_mode), (height, y_mode)];
+
+    for i in 0..count {
+        for (k, &(size, mode)) in axes.iter().enumerate() {
+            let idx = i * 2 + k;
+            let p = &mut positions[idx];
+            let v = &mut velocities[idx];
+
+            match mode {
+                EDGE_WRAP => {
+                    if *p < 0.0 {
+                        *p += size;
+                    } else if *p >= size {
+                        *p -= size;
+                    }
+                }
+                EDGE_BOUNCE => bounce_axis(p, v, size, 1.0),
+                EDGE_FORCE if margin > 0.0 => {
+                    if let Some(a) = out_accel.get_mut(idx) {
+                        *a += edge_push(*p, size, margin) * strength;
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Batch pin positions inside a rectangle.
+///
+/// Coordinates outside `[min_x, max_x] × [min_y, max_y]` are moved to the
+/// nearest edge; velocities are not touched.
+///
+/// # Arguments
+/// * `positions` - Mutable array of positions (x, y pairs)
+/// * `min_x`, `min_y` - Top-left corner of the rectangle
+/// * `max_x`, `max_y` - Bottom-right corner of the rectangle
+#[wasm_bindgen]
+pub fn clamp_positions_rect(positions: &mut [f32], min_x: f32, min_y: f32, max_x: f32, max_y: f32) {
+    for p in positions.chunks_exact_mut(2) {
+        p[0] = p[0].max(min_x).min(max_x);
+        p[1] = p[1].max(min_y).min(max_y);
+    }
+}
+
+/// Batch reflect velocities off per-boid surface normals.
+///
+/// For each boid flagged in `hit_mask`, the velocity component along the
+/// normal is flipped and scaled by `restitution`, leaving the tangential
+/// component untouched: `v - (1 + restitution) (v · n) n`. Normals need not
+/// be unit length. Boids already moving away from the surface, and those
+/// with a zero or missing normal, are left unchanged.
+///
+/// # Arguments
+/// * `velocities` - Mutable array of velocities (x, y pairs)
+/// * `hit_mask` - Nonzero for boids that hit a surface
+/// * `normal_x` - Surface normal X per boid
+/// * `normal_y` - Surface normal Y per boid
+/// * `restitution` - Fraction of the normal speed kept (1 = elastic)
+#[wasm_bindgen]
+pub fn reflect_velocities(
+    velocities: &mut [f32],
+    hit_mask: &[u8],
+    normal_x: &[f32],
+    normal_y: &[f32],
+    restitution: f32,
+) {
+    let hits = hit_mask.iter().zip(normal_x).zip(normal_y);
+
+    for (v, ((&hit, &nx), &ny)) in velocities.chunks_exact_mut(2).zip(hits) {
+        let len_sq = nx * nx + ny * ny;
+        if hit == 0 || len_sq <= 0.0 {
+            continue;
+        }
+
+        let dot = (v[0] * nx + v[1] * ny) / len_sq;
+        if dot < 0.0 {
+            let scale = (1.0 + restitution) * dot;
+            v[0] -= scale * nx;
+            v[1] -= scale * ny;
+        }
+    }
+}
+
+/// Batch keep boids inside a disc.
+///
+/// A boid outside the circle is mirrored back across the boundary along the
+/// radial normal, the way [`bounce_positions_all`](crate::bounce_positions_all)
+/// does at rectangle walls, and its outward radial velocity is flipped and
+/// scaled by `restitution`. The tangential velocity is kept.
+///
+/// # Arguments
+/// * `positions` - Mutable array of positions (x, y pairs)
+/// * `velocities` - Mutable array of velocities (x, y pairs)
+/// * `center_x` - Circle center X
+/// * `center_y` - Circle center Y
+/// * `radius` - Circle radius
+/// * `restitution` - Fraction of the radial speed kept (1 = elastic)
+#[wasm_bindgen]
+pub fn contain_in_circle(
+    positions: &mut [f32],
+    velocities: &mut [f32],
+    center_x: f32,
+    center_y: f32,
+    radius: f32,
+    restitution: f32,
+) {
+    if radius <= 0.0 {
+        return;
+    }
+
+    for (p, v) in positions
+        .chunks_exact_mut(2)
+        .zip(velocities.chunks_exact_mut(2))
+    {
+        let dx = p[0] - center_x;
+        let dy = p[1] - center_y;
+        let dist = (dx * dx + dy * dy).sqrt();
+        if dist <= radius {
+            continue;
+        }
+
+        let nx = dx / dist;
+        let ny = dy / dist;
+        // Overshoot larger than the diameter lands on the far side's edge
+        let inside = (2.0 * radius - dist).max(-radius);
+        p[0] = center_x + nx * inside;
+        p[1] = center_y + ny * inside;
+
+        let radial = v[0] * nx + v[1] * ny;
+        if radial > 0.0 {
+            let scale = (1.0 + restitution) * radial;
+            v[0] -= scale * nx;
+            v[1] -= scale * ny;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_boundary_force() {
+        let positions = vec![5.0, 50.0, 50.0, 50.0, 95.0, 95.0];
+        let mut accel = vec![0.0; 6];
+
+        boundary_force_all(&positions, &mut accel, 100.0, 100.0, 20.0, 1.0);
+
+        assert!((accel[0] - 0.75).abs() < 0.001);
+        assert_eq!(accel[1], 0.0);
+        assert_eq!(accel[2..4], [0.0, 0.0]);
+        // Corner sums both axes
+        assert!((accel[4] + 0.75).abs() < 0.001);
+        assert!((accel[5] + 0.75).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_bounds_wrap_x_bounce_y() {
+        let mut positions = vec![102.0, -3.0];
+        let mut velocities = vec![2.0, -3.0];
+        let mut accel = vec![0.0; 2];
+
+        apply_bounds_all(
+            &mut positions,
+            &mut velocities,
+            &mut accel,
+            100.0,
+            100.0,
+            EDGE_WRAP,
+            EDGE_BOUNCE,
+            0.0,
+            0.0,
+        );
+
+        assert_eq!(positions, vec![2.0, 3.0]);
+        assert_eq!(velocities, vec![2.0, 3.0]);
+        assert_eq!(accel, vec![0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_bounds_force_mode_adds_to_accel() {
+        let mut positions = vec![2.0, 50.0];
+        let mut velocities = vec![-1.0, 0.0];
+        let mut accel = vec![0.0; 2];
+
+        apply_bounds_all(
+            &mut positions,
+            &mut velocities,
+            &mut accel,
+            100.0,
+            100.0,
+            EDGE_FORCE,
+            EDGE_FORCE,
+            10.0,
+            1.0,
+        );
+
+        // Same push as boundary_force_all, velocity left for integration
+        let mut expected = vec![0.0; 2];
+        boundary_force_all(&positions, &mut expected, 100.0, 100.0, 10.0, 1.0);
+        assert_eq!(accel, expected);
+        assert_eq!(velocities, vec![-1.0, 0.0]);
+    }
+
+    #[test]
+    fn test_clamp_positions_rect() {
+        let mut positions = vec![-5.0, 200.0, 40.0, 60.0];
+
+        clamp_positions_rect(&mut positions, 0.0, 0.0, 100.0, 100.0);
+
+        assert_eq!(positions, vec![0.0, 100.0, 40.0, 60.0]);
+    }
+
+    #[test]
+    fn test_reflect_velocities() {
+        let mut velocities = vec![1.0, -1.0, 1.0, -1.0, 2.0, -4.0, 0.0, 3.0];
+
+        reflect_velocities(
+            &mut velocities,
+            &[1, 0, 1, 1],
+            &[0.0, 0.0, 0.0, 0.0],
+            &[1.0, 1.0, 2.0, 1.0],
+            1.0,
+        );
+        assert_eq!(&velocities[..2], &[1.0, 1.0]);
+        // Not flagged
+        assert_eq!(&velocities[2..4], &[1.0, -1.0]);
+        // Unnormalized normal, still elastic
+        assert_eq!(&velocities[4..6], &[2.0, 4.0]);
+        // Already moving away
+        assert_eq!(&velocities[6..], &[0.0, 3.0]);
+
+        let mut velocities = vec![1.0, -2.0];
+        reflect_velocities(&mut velocities, &[1], &[0.0], &[1.0], 0.5);
+        assert_eq!(velocities, vec![1.0, 1.0]);
+    }
+
+    #[test]
+    fn test_contain_in_circle() {
+        // Center (10, 10), radius 5: boid 0 escaped to the right, boid 1 is inside
+        let mut positions = vec![16.0, 10.0, 12.0, 10.0];
+        let mut velocities = vec![3.0, 1.0, 3.0, 1.0];
+
+        contain_in_circle(&mut positions, &mut velocities, 10.0, 10.0, 5.0, 1.0);
+
+        assert_eq!(&positions[..2], &[14.0, 10.0]);
+        assert_eq!(&velocities[..2], &[-3.0, 1.0]);
+        assert_eq!(&positions[2..], &[12.0, 10.0]);
+        assert_eq!(&velocities[2..], &[3.0, 1.0]);
+    }
+}
@@ -0,0 +1,205 @@
+//! WASM-owned flock state.
+//!
+//! Allocating and copying typed arrays every frame puts pressure on the JS
+//! garbage collector. `FlockBuffers` keeps the state in WASM memory instead;
+//! JS builds `Float32Array` views over it once, from the exposed pointers.
+
+use wasm_bindgen::prelude::*;
+
+use crate::params::SimParams;
+use crate::simulation::simulate_step;
+
+/// Positions, velocities and accelerations for a flock, stored interleaved.
+///
+/// Each buffer holds `2 * len()` floats. The positions from before the last
+/// step are kept too, for trails and motion blur. Views built from the
+/// pointers are invalidated if WASM memory grows, so JS should rebuild them
+/// after any call that may allocate.
+#[wasm_bindgen]
+pub struct FlockBuffers {
+    positions: Vec<f32>,
+    prev_positions: Vec<f32>,
+    velocities: Vec<f32>,
+    accelerations: Vec<f32>,
+}
+
+#[wasm_bindgen]
+impl FlockBuffers {
+    /// Allocate zeroed buffers for `count` boids.
+    #[wasm_bindgen(constructor)]
+    pub fn new(count: usize) -> FlockBuffers {
+        FlockBuffers {
+            positions: vec![0.0; count * 2],
+            prev_positions: vec![0.0; count * 2],
+            velocities: vec![0.0; count * 2],
+            accelerations: vec![0.0; count * 2],
+        }
+    }
+
+    /// Number of boids.
+    pub fn len(&self) -> usize {
+        self.positions.len() / 2
+    }
+
+    /// Whether the flock has no boids.
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    /// Pointer to the positions buffer in WASM memory.
+    pub fn positions_ptr(&self) -> *const f32 {
+        self.positions.as_ptr()
+    }
+
+    /// Pointer to the positions from before the last step, in WASM memory.
+    pub fn prev_positions_ptr(&self) -> *const f32 {
+        self.prev_positions.as_ptr()
+    }
+
+    /// Pointer to the velocities buffer in WASM memory.
+    pub fn velocities_ptr(&self) -> *const f32 {
+        self.velocities.as_ptr()
+    }
+
+    /// Memory layout of the buffers, for building `Float32Array` views.
+    ///
+    /// Returns `[pos_offset, pos_len, vel_offset, vel_len, acc_offset,
+    /// acc_len]`: byte offsets into WASM linear memory and lengths in
+    /// floats. Like the pointers, the offsets are only valid until memory
+    /// grows. Offsets are truncated to 32 bits outside wasm32.
+    pub fn buffer_layout(&self) -> Vec<u32> {
+        [&self.positions, &self.velocities, &self.accelerations]
+            .iter()
+            .flat_map(|buf| [buf.as_ptr() as usize as u32, buf.len() as u32])
+            .collect()
+    }
+
+    /// Copy positions in, up to the buffer length.
+    ///
+    /// The previous positions are set too, so teleported boids don't streak.
+    pub fn set_positions(&mut self, positions: &[f32]) {
+        let n = positions.len().min(self.positions.len());
+        self.positions[..n].copy_from_slice(&positions[..n]);
+        self.prev_positions[..n].copy_from_slice(&positions[..n]);
+    }
+
+    /// Copy velocities in, up to the buffer length.
+    pub fn set_velocities(&mut self, velocities: &[f32]) {
+        let n = velocities.len().min(self.velocities.len());
+        self.velocities[..n].copy_from_slice(&velocities[..n]);
+    }
+
+    /// Advance the flock one step in place; see [`simulate_step`].
+    pub fn step(&mut self, params: &SimParams) {
+        self.save_prev_positions();
+        self.advance(params);
+    }
+}
+
+impl FlockBuffers {
+    /// Remember the current positions as the previous ones.
+    pub(crate) fn save_prev_positions(&mut self) {
+        self.prev_positions.copy_from_slice(&self.positions);
+    }
+
+    /// [`step`](Self::step) without updating the previous positions.
+    pub(crate) fn advance(&mut self, params: &SimParams) {
+        simulate_step(
+            &mut self.positions,
+            &mut self.velocities,
+            &mut self.accelerations,
+            params,
+        );
+    }
+
+    /// Positions as a slice, for Rust callers.
+    pub fn positions(&self) -> &[f32] {
+        &self.positions
+    }
+
+    /// Velocities as a slice, for Rust callers.
+    pub fn velocities(&self) -> &[f32] {
+        &self.velocities
+    }
+
+    /// Positions from before the last step, for Rust callers.
+    pub fn prev_positions(&self) -> &[f32] {
+        &self.prev_positions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flock_buffers_step() {
+        let mut buffers = FlockBuffers::new(10);
+        assert_eq!(buffers.len(), 10);
+
+        let positions: Vec<f32> = (0..20).map(|i| 100.0 + i as f32 * 30.0).collect();
+        let velocities: Vec<f32> = (0..20)
+            .map(|i| if i % 2 == 0 { 2.0 } else { 0.0 })
+            .collect();
+        buffers.set_positions(&positions);
+        buffers.set_velocities(&velocities);
+
+        let mut expected_pos = positions.clone();
+        let mut expected_vel = velocities.clone();
+        let mut accel = vec![0.0; 20];
+        let params = SimParams::new();
+        simulate_step(&mut expected_pos, &mut expected_vel, &mut accel, &params);
+
+        buffers.step(&params);
+
+        // The pointer view sees the same data as the slice accessor
+        let view = unsafe { std::slice::from_raw_parts(buffers.positions_ptr(), 20) };
+        assert_eq!(view, expected_pos.as_slice());
+        assert_eq!(buffers.positions(), expected_pos.as_slice());
+        assert_eq!(buffers.velocities(), expected_vel.as_slice());
+        assert_ne!(buffers.positions(), positions.as_slice());
+    }
+
+    #[test]
+    fn test_prev_positions_track_last_step() {
+        let positions: Vec<f32> = (0..12).map(|i| 50.0 + i as f32 * 40.0).collect();
+        let mut buffers = FlockBuffers::new(6);
+        buffers.set_positions(&positions);
+        buffers.set_velocities(&[2.0; 12]);
+        assert_eq!(buffers.prev_positions(), positions.as_slice());
+
+        let params = SimParams::new();
+        buffers.step(&params);
+        assert_eq!(buffers.prev_positions(), positions.as_slice());
+
+        let before = buffers.positions().to_vec();
+        buffers.step(&params);
+        let view = unsafe { std::slice::from_raw_parts(buffers.prev_positions_ptr(), 12) };
+        assert_eq!(view, before.as_slice());
+        assert_ne!(buffers.positions(), before.as_slice());
+    }
+
+    #[test]
+    fn test_buffer_layout() {
+        let mut buffers = FlockBuffers::new(3);
+        let positions = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0];
+        let velocities = [-1.0, -2.0, -3.0, -4.0, -5.0, -6.0];
+        buffers.set_positions(&positions);
+        buffers.set_velocities(&velocities);
+
+        let layout = buffers.buffer_layout();
+        assert_eq!(layout.len(), 6);
+        assert_eq!([layout[1], layout[3], layout[5]], [6, 6, 6]);
+        assert_eq!(layout[0], buffers.positions_ptr() as usize as u32);
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            let view = |k: usize| unsafe {
+                std::slice::from_raw_parts(layout[k] as usize as *const f32, layout[k + 1] as usize)
+            };
+            assert_eq!(view(0), &positions);
+            assert_eq!(view(2), &velocities);
+            assert_eq!(view(4), &[0.0; 6]);
+        }
+    }
+}
@@ -0,0 +1,234 @@
+//! Boid-boid collision handling.
+//!
+//! Unlike separation, which only biases steering, these functions keep boids
+//! from overlapping: either by correcting positions directly or by steering
+//! ahead of a predicted collision.
+
+use wasm_bindgen::prelude::*;
+
+use crate::grid::SpatialGrid;
+use crate::{add_pair, fallback_heading, read_pair};
+
+/// Push overlapping boids apart until they no longer overlap.
+///
+/// Each boid is a circle of its own radius. Every overlapping pair is moved
+/// apart along the line between them, each boid by half the penetration,
+/// and the pass is repeated `iterations` times so chains of contacts settle.
+/// Exactly coincident boids are separated along a stable per-index heading.
+///
+/// `grid` must have been rebuilt from `positions`; it is not updated between
+/// iterations, so its cell size should be at least the largest diameter.
+/// Boids without a radius entry have radius 0.
+///
+/// # Arguments
+/// * `positions` - Mutable array of positions (x, y pairs)
+/// * `radii` - Collision radius of each boid
+/// * `grid` - Spatial grid built from `positions`
+/// * `iterations` - Number of relaxation passes
+#[wasm_bindgen]
+pub fn resolve_collisions(
+    positions: &mut [f32],
+    radii: &[f32],
+    grid: &SpatialGrid,
+    iterations: u32,
+) {
+    let count = positions.len() / 2;
+    let radius = |i: usize| radii.get(i).copied().unwrap_or(0.0);
+    let max_radius = radii.iter().take(count).copied().fold(0.0, f32::max);
+
+    for _ in 0..iterations {
+        for i in 0..count {
+            let (px, py) = read_pair(positions, i);
+            let ri = radius(i);
+
+            for j in grid.candidates(px, py, ri + max_radius) {
+                if j <= i || j >= count {
+                    continue;
+                }
+
+                let (px, py) = read_pair(positions, i);
+                let (qx, qy) = read_pair(positions, j);
+                let min_dist = ri + radius(j);
+                let dx = qx - px;
+                let dy = qy - py;
+                let dist_sq = dx * dx + dy * dy;
+
+                if dist_sq >= min_dist * min_dist {
+                    continue;
+                }
+
+                let dist = dist_sq.sqrt();
+                let (nx, ny) = if dist > 0.0 {
+                    (dx / dist, dy / dist)
+                } else {
+                    fallback_heading(i)
+                };
+                let push = (min_dist - dist) * 0.5;
+
+                positions[i * 2] -= nx * push;
+                positions[i * 2 + 1] -= ny * push;
+                positions[j * 2] += nx * push;
+                positions[j * 2 + 1] += ny * push;
+            }
+        }
+    }
+}
+
+/// Batch steer boids to sidestep predicted collisions.
+///
+/// For every other boid, both are assumed to keep their current velocities
+/// and the time of closest approach is computed. If that lies within
+/// `time_horizon` and the boids would pass closer than `radius`, the boid is
+/// pushed away from the neighbor's predicted offset, harder the sooner the
+/// approach. Exactly head-on pairs sidestep to the right of their relative
+/// velocity, so both sides turn apart.
+///
+/// # Arguments
+/// * `positions` - Array of positions (x, y pairs)
+/// * `velocities` - Array of velocities (x, y pairs)
+/// * `out_accel` - Mutable array of accelerations to add into (x, y pairs)
+/// * `radius` - Distance closer than which boids count as colliding
+/// * `time_horizon` - How far ahead to look for collisions
+/// * `strength` - Force magnitude for an immediate collision
+#[wasm_bindgen]
+pub fn avoid_collisions_rvo(
+    positions: &[f32],
+    velocities: &[f32],
+    out_accel: &mut [f32],
+    radius: f32,
+    time_horizon: f32,
+    strength: f32,
+) {
+    let count = positions.len() / 2;
+    let radius_sq = radius * radius;
+
+    for i in 0..count {
+        let (px, py) = read_pair(positions, i);
+        let (vx, vy) = read_pair(velocities, i);
+        let mut fx = 0.0;
+        let mut fy = 0.0;
+
+        for j in 0..count {
+            if j == i {
+                continue;
+            }
+
+            let (qx, qy) = read_pair(positions, j);
+            let (ux, uy) = read_pair(velocities, j);
+            let (dx, dy) = (qx - px, qy - py);
+            let (wx, wy) = (ux - vx, uy - vy);
+
+            let w_sq = wx * wx + wy * wy;
+            if w_sq <= 0.0 {
+                continue;
+            }
+
+            let t = -(dx * wx + dy * wy) / w_sq;
+            if t <= 0.0 || t >= time_horizon {
+                continue;
+            }
+
+            // Neighbor's offset at closest approach
+            let sx = dx + wx * t;
+            let sy = dy + wy * t;
+            let s_sq = sx * sx + sy * sy;
+            if s_sq >= radius_sq {
+                continue;
+            }
+
+            let (nx, ny) = if s_sq > 1e-8 {
+                let s = s_sq.sqrt();
+                (-sx / s, -sy / s)
+            } else {
+                let w = w_sq.sqrt();
+                (wy / w, -wx / w)
+            };
+            let urgency = strength * (1.0 - t / time_horizon);
+            fx += nx * urgency;
+            fy += ny * urgency;
+        }
+
+        add_pair(out_accel, i, fx, fy);
+    }
+}
+
+/// Deactivate every prey boid within a predator's strike radius.
+///
+/// Sets `active[i] = 0` for active boids closer than `strike_radius` to the
+/// predator and returns how many were caught. Boids already inactive, or
+/// without an `active` entry, are ignored. The mask matches the one
+/// [`integrate_all_masked`](crate::integrate_all_masked) takes.
+///
+/// # Arguments
+/// * `positions` - Array of positions (x, y pairs)
+/// * `active` - Mutable per-boid flag, nonzero for live prey
+/// * `predator_x` - Predator X coordinate
+/// * `predator_y` - Predator Y coordinate
+/// * `strike_radius` - Catch radius
+#[wasm_bindgen]
+pub fn predator_strike(
+    positions: &[f32],
+    active: &mut [u8],
+    predator_x: f32,
+    predator_y: f32,
+    strike_radius: f32,
+) -> u32 {
+    let radius_sq = strike_radius * strike_radius;
+    let mut caught = 0;
+
+    for (p, flag) in positions.chunks_exact(2).zip(active.iter_mut()) {
+        let dx = p[0] - predator_x;
+        let dy = p[1] - predator_y;
+        if *flag != 0 && dx * dx + dy * dy < radius_sq {
+            *flag = 0;
+            caught += 1;
+        }
+    }
+
+    caught
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_collisions_separates_pair() {
+        let mut positions = vec![5.0, 5.0, 6.0, 5.0, 20.0, 20.0];
+        let mut grid = SpatialGrid::new(4.0);
+        grid.rebuild(&positions, 40.0, 40.0);
+
+        resolve_collisions(&mut positions, &[1.0; 3], &grid, 4);
+
+        let dx = positions[2] - positions[0];
+        let dy = positions[3] - positions[1];
+        assert!((dx * dx + dy * dy).sqrt() >= 2.0);
+        assert_eq!(&positions[4..], &[20.0, 20.0]);
+    }
+
+    #[test]
+    fn test_rvo_head_on_sidesteps() {
+        let positions = vec![0.0, 0.0, 10.0, 0.0, 0.0, 50.0];
+        let velocities = vec![1.0, 0.0, -1.0, 0.0, 1.0, 0.0];
+        let mut accel = vec![0.0; 6];
+
+        avoid_collisions_rvo(&positions, &velocities, &mut accel, 2.0, 10.0, 1.0);
+
+        // Impact in 5 of 10 time units: half strength, lateral and opposite
+        assert_eq!(accel[0], 0.0);
+        assert!((accel[1].abs() - 0.5).abs() < 1e-6);
+        assert_eq!(accel[3], -accel[1]);
+        assert_eq!(&accel[4..], &[0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_predator_strike() {
+        let positions = vec![1.0, 1.0, 0.0, 2.5, 10.0, 0.0, -1.0, 0.0];
+        let mut active = vec![1, 1, 1, 0];
+
+        assert_eq!(predator_strike(&positions, &mut active, 0.0, 0.0, 3.0), 2);
+        // The far boid survives; the dead one stays dead and isn't recounted
+        assert_eq!(active, vec![0, 0, 1, 0]);
+        assert_eq!(predator_strike(&positions, &mut active, 0.0, 0.0, 3.0), 0);
+    }
+}
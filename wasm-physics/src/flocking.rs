@@ -0,0 +1,1217 @@
+//! Classic boid steering forces: separation, alignment and cohesion.
+//!
+//! Every function adds into `out_accel` rather than overwriting it, so the
+//! forces compose with each other and with global forces. Each force has a
+//! brute-force O(n²) entry point and a `_grid` variant that only scans the
+//! cells of a [`SpatialGrid`] around each boid. The `_fov` variants ignore
+//! neighbors outside each boid's view cone.
+
+use wasm_bindgen::prelude::*;
+
+use crate::grid::SpatialGrid;
+use crate::scratch::Scratch;
+use crate::stats::flock_centroid;
+use crate::{add_pair, read_pair, wrap_delta};
+
+/// Falloff: every neighbor within the radius counts equally.
+pub const FALLOFF_CONSTANT: u8 = 0;
+/// Falloff: weight drops linearly to zero at the radius.
+pub const FALLOFF_LINEAR: u8 = 1;
+/// Falloff: weight `1 / d`.
+pub const FALLOFF_INVERSE: u8 = 2;
+/// Falloff: weight `1 / d²`.
+pub const FALLOFF_INVERSE_SQUARE: u8 = 3;
+/// Falloff: weight eases from 1 at contact to zero at the radius.
+pub const FALLOFF_SMOOTHSTEP: u8 = 4;
+
+/// Batch compute separation forces.
+///
+/// Each boid is pushed away from every neighbor within `radius` along the
+/// unit direction, scaled by the `falloff` weight of the neighbor's
+/// distance. [`FALLOFF_INVERSE`] gives the classic inverse-distance
+/// separation used by the other variants. Boids sharing the exact same
+/// position have no defined direction and are skipped.
+///
+/// # Arguments
+/// * `positions` - Array of positions (x, y pairs)
+/// * `out_accel` - Mutable array of accelerations to add into (x, y pairs)
+/// * `radius` - Neighbor radius
+/// * `strength` - Force multiplier
+/// * `falloff` - Distance weighting, one of the `FALLOFF_*` constants
+#[wasm_bindgen]
+pub fn compute_separation(
+    positions: &[f32],
+    out_accel: &mut [f32],
+    radius: f32,
+    strength: f32,
+    falloff: u8,
+) {
+    let count = positions.len() / 2;
+    let weight = |dist_sq: f32| falloff_weight(falloff, dist_sq, radius) / dist_sq.sqrt();
+
+    for i in 0..count {
+        let (fx, fy) = separation_with(positions, i, 0..count, radius, |dx, dy| (dx, dy), weight);
+        add_pair(out_accel, i, fx * strength, fy * strength);
+    }
+}
+
+/// Grid-accelerated [`compute_separation`].
+///
+/// `grid` must have been rebuilt from `positions`. Each pair of neighbors
+/// is tested once, with both boids' sums accumulated in `scratch`. With
+/// `max_neighbors` set, each boid instead scans its own neighbors and
+/// considers only the first that many found, bounding the cost in dense
+/// clusters at the expense of accuracy; `scratch` is then unused.
+///
+/// # Arguments
+/// * `positions` - Array of positions (x, y pairs)
+/// * `grid` - Spatial grid rebuilt from `positions`
+/// * `out_accel` - Mutable array of accelerations to add into (x, y pairs)
+/// * `radius` - Neighbor radius
+/// * `strength` - Force multiplier
+/// * `max_neighbors` - Optional cap on neighbors considered per boid
+/// * `scratch` - Reusable accumulators
+#[wasm_bindgen]
+pub fn compute_separation_grid(
+    positions: &[f32],
+    grid: &SpatialGrid,
+    out_accel: &mut [f32],
+    radius: f32,
+    strength: f32,
+    max_neighbors: Option<u32>,
+    scratch: &mut Scratch,
+) {
+    let count = positions.len() / 2;
+
+    if max_neighbors.is_some() {
+        for i in 0..count {
+            let candidates = grid_neighbors(positions, grid, i, radius, max_neighbors);
+            let (fx, fy) = separation_for(positions, i, candidates, radius);
+            add_pair(out_accel, i, fx * strength, fy * strength);
+        }
+        return;
+    }
+
+    let (sums, _) = scratch.reset(count);
+    for_each_pair(positions, grid, radius, |i, j, dx, dy, dist_sq| {
+        if dist_sq > 0.0 {
+            let w = inverse_distance(dist_sq);
+            add_pair(sums, i, dx * w, dy * w);
+            add_pair(sums, j, -dx * w, -dy * w);
+        }
+    });
+
+    for i in 0..count {
+        let (fx, fy) = read_pair(sums, i);
+        add_pair(out_accel, i, fx * strength, fy * strength);
+    }
+}
+
+/// Torus-aware [`compute_separation`].
+///
+/// Offsets between boids take the shortest path across the world edges, so
+/// boids near opposite sides repel each other across the seam.
+///
+/// # Arguments
+/// * `positions` - Array of positions (x, y pairs)
+/// * `out_accel` - Mutable array of accelerations to add into (x, y pairs)
+/// * `radius` - Neighbor radius
+/// * `strength` - Force multiplier
+/// * `width` - World width
+/// * `height` - World height
+#[wasm_bindgen]
+pub fn compute_separation_wrapped(
+    positions: &[f32],
+    out_accel: &mut [f32],
+    radius: f32,
+    strength: f32,
+    width: f32,
+    height: f32,
+) {
+    let count = positions.len() / 2;
+    let delta = |dx, dy| (wrap_delta(dx, width), wrap_delta(dy, height));
+
+    for i in 0..count {
+        let (fx, fy) = separation_with(positions, i, 0..count, radius, delta, inverse_distance);
+        add_pair(out_accel, i, fx * strength, fy * strength);
+    }
+}
+
+/// [`compute_separation`] with a bounded, smooth falloff.
+///
+/// Each neighbor pushes with weight `1 - smoothstep(desired_dist, radius, d)`
+/// along the unit direction away from it: zero at `radius`, rising to 1 at
+/// `desired_dist` and staying at 1 closer in. Unlike the inverse-distance
+/// weighting, overlapping boids get a bounded push of at most `strength` per
+/// neighbor.
+///
+/// # Arguments
+/// * `positions` - Array of positions (x, y pairs)
+/// * `out_accel` - Mutable array of accelerations to add into (x, y pairs)
+/// * `radius` - Neighbor radius
+/// * `strength` - Force multiplier
+/// * `desired_dist` - Distance below which the push is at full strength
+#[wasm_bindgen]
+pub fn compute_separation_smooth(
+    positions: &[f32],
+    out_accel: &mut [f32],
+    radius: f32,
+    strength: f32,
+    desired_dist: f32,
+) {
+    let count = positions.len() / 2;
+    let span = radius - desired_dist;
+    let weight = |dist_sq: f32| {
+        let dist = dist_sq.sqrt();
+        let t = if span > 0.0 {
+            ((dist - desired_dist) / span).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        (1.0 - t * t * (3.0 - 2.0 * t)) / dist
+    };
+
+    for i in 0..count {
+        let (fx, fy) = separation_with(positions, i, 0..count, radius, |dx, dy| (dx, dy), weight);
+        add_pair(out_accel, i, fx * strength, fy * strength);
+    }
+}
+
+/// Batch compute alignment forces.
+///
+/// Each boid steers toward the average velocity of its neighbors within
+/// `radius`, weighted by the `falloff` of each neighbor's distance.
+/// [`FALLOFF_CONSTANT`] gives the plain average. Boids with no neighbors
+/// receive no force.
+///
+/// # Arguments
+/// * `positions` - Array of positions (x, y pairs)
+/// * `velocities` - Array of velocities (x, y pairs)
+/// * `out_accel` - Mutable array of accelerations to add into (x, y pairs)
+/// * `radius` - Neighbor radius
+/// * `strength` - Force multiplier
+/// * `falloff` - Distance weighting, one of the `FALLOFF_*` constants
+#[wasm_bindgen]
+pub fn compute_alignment(
+    positions: &[f32],
+    velocities: &[f32],
+    out_accel: &mut [f32],
+    radius: f32,
+    strength: f32,
+    falloff: u8,
+) {
+    let count = positions.len() / 2;
+    let weight = |dist_sq| falloff_weight(falloff, dist_sq, radius);
+
+    for i in 0..count {
+        let (fx, fy) = alignment_for(positions, velocities, i, 0..count, radius, weight);
+        add_pair(out_accel, i, fx * strength, fy * strength);
+    }
+}
+
+/// Grid-accelerated [`compute_alignment`].
+///
+/// `grid` must have been rebuilt from `positions`. Each pair of neighbors
+/// is tested once, with both boids' sums accumulated in `scratch`. With
+/// `max_neighbors` set, each boid instead scans its own neighbors and
+/// considers only the first that many found, bounding the cost in dense
+/// clusters at the expense of accuracy; `scratch` is then unused.
+///
+/// # Arguments
+/// * `positions` - Array of positions (x, y pairs)
+/// * `velocities` - Array of velocities (x, y pairs)
+/// * `grid` - Spatial grid rebuilt from `positions`
+/// * `out_accel` - Mutable array of accelerations to add into (x, y pairs)
+/// * `radius` - Neighbor radius
+/// * `strength` - Force multiplier
+/// * `max_neighbors` - Optional cap on neighbors considered per boid
+/// * `scratch` - Reusable accumulators
+#[wasm_bindgen]
+#[allow(clippy::too_many_arguments)]
+pub fn compute_alignment_grid(
+    positions: &[f32],
+    velocities: &[f32],
+    grid: &SpatialGrid,
+    out_accel: &mut [f32],
+    radius: f32,
+    strength: f32,
+    max_neighbors: Option<u32>,
+    scratch: &mut Scratch,
+) {
+    let count = positions.len() / 2;
+
+    if max_neighbors.is_some() {
+        for i in 0..count {
+            let candidates = grid_neighbors(positions, grid, i, radius, max_neighbors);
+            let (fx, fy) = alignment_for(positions, velocities, i, candidates, radius, uniform);
+            add_pair(out_accel, i, fx * strength, fy * strength);
+        }
+        return;
+    }
+
+    let (sums, weights) = scratch.reset(count);
+    for_each_pair(positions, grid, radius, |i, j, _, _, _| {
+        let (ix, iy) = read_pair(velocities, i);
+        let (jx, jy) = read_pair(velocities, j);
+        add_pair(sums, i, jx, jy);
+        add_pair(sums, j, ix, iy);
+        weights[i] += 1.0;
+        weights[j] += 1.0;
+    });
+
+    for (i, &weight) in weights.iter().enumerate() {
+        if weight > 0.0 {
+            let inv = 1.0 / weight;
+            let (sx, sy) = read_pair(sums, i);
+            let (vx, vy) = read_pair(velocities, i);
+            add_pair(
+                out_accel,
+                i,
+                (sx * inv - vx) * strength,
+                (sy * inv - vy) * strength,
+            );
+        }
+    }
+}
+
+/// Batch compute cohesion forces.
+///
+/// Each boid steers toward the centroid of its neighbors within `radius`
+/// (excluding itself) with a force of magnitude `strength`. The centroid is
+/// weighted by the `falloff` of each neighbor's distance;
+/// [`FALLOFF_CONSTANT`] gives the plain centroid. Isolated boids receive no
+/// force.
+///
+/// With `density_compensation`, the force is divided by the square root of
+/// the neighbor count, so large groups don't pull together so hard that
+/// they overwhelm separation.
+///
+/// # Arguments
+/// * `positions` - Array of positions (x, y pairs)
+/// * `out_accel` - Mutable array of accelerations to add into (x, y pairs)
+/// * `radius` - Neighbor radius
+/// * `strength` - Force magnitude
+/// * `density_compensation` - Scale the force by `1 / sqrt(neighbor_count)`
+/// * `falloff` - Distance weighting, one of the `FALLOFF_*` constants
+#[wasm_bindgen]
+pub fn compute_cohesion(
+    positions: &[f32],
+    out_accel: &mut [f32],
+    radius: f32,
+    strength: f32,
+    density_compensation: bool,
+    falloff: u8,
+) {
+    let count = positions.len() / 2;
+    let weight = |dist_sq| falloff_weight(falloff, dist_sq, radius);
+
+    for i in 0..count {
+        let (fx, fy, neighbors) = cohesion_for(positions, i, 0..count, radius, weight);
+        let scale = if density_compensation && neighbors > 0 {
+            strength / (neighbors as f32).sqrt()
+        } else {
+            strength
+        };
+        add_pair(out_accel, i, fx * scale, fy * scale);
+    }
+}
+
+/// Grid-accelerated [`compute_cohesion`].
+///
+/// `grid` must have been rebuilt from `positions`. Each pair of neighbors
+/// is tested once, with both boids' sums accumulated in `scratch`. With
+/// `max_neighbors` set, each boid instead scans its own neighbors and
+/// considers only the first that many found, bounding the cost in dense
+/// clusters at the expense of accuracy; `scratch` is then unused.
+///
+/// # Arguments
+/// * `positions` - Array of positions (x, y pairs)
+/// * `grid` - Spatial grid rebuilt from `positions`
+/// * `out_accel` - Mutable array of accelerations to add into (x, y pairs)
+/// * `radius` - Neighbor radius
+/// * `strength` - Force magnitude
+/// * `max_neighbors` - Optional cap on neighbors considered per boid
+/// * `scratch` - Reusable accumulators
+#[wasm_bindgen]
+pub fn compute_cohesion_grid(
+    positions: &[f32],
+    grid: &SpatialGrid,
+    out_accel: &mut [f32],
+    radius: f32,
+    strength: f32,
+    max_neighbors: Option<u32>,
+    scratch: &mut Scratch,
+) {
+    let count = positions.len() / 2;
+
+    if max_neighbors.is_some() {
+        for i in 0..count {
+            let candidates = grid_neighbors(positions, grid, i, radius, max_neighbors);
+            let (fx, fy, _) = cohesion_for(positions, i, candidates, radius, uniform);
+            add_pair(out_accel, i, fx * strength, fy * strength);
+        }
+        return;
+    }
+
+    let (sums, weights) = scratch.reset(count);
+    for_each_pair(positions, grid, radius, |i, j, _, _, _| {
+        let (ix, iy) = read_pair(positions, i);
+        let (jx, jy) = read_pair(positions, j);
+        add_pair(sums, i, jx, jy);
+        add_pair(sums, j, ix, iy);
+        weights[i] += 1.0;
+        weights[j] += 1.0;
+    });
+
+    for (i, &weight) in weights.iter().enumerate() {
+        if weight > 0.0 {
+            let inv = 1.0 / weight;
+            let (sx, sy) = read_pair(sums, i);
+            let (px, py) = read_pair(positions, i);
+            let to_x = sx * inv - px;
+            let to_y = sy * inv - py;
+            let len = (to_x * to_x + to_y * to_y).sqrt();
+
+            if len > 0.0 {
+                let scale = strength / len;
+                add_pair(out_accel, i, to_x * scale, to_y * scale);
+            }
+        }
+    }
+}
+
+/// Batch steer every boid toward the centroid of the whole flock.
+///
+/// An O(n) alternative to [`compute_cohesion`] for tight global swarming.
+/// Each boid gets a force of magnitude `strength`; a boid sitting exactly on
+/// the centroid gets none.
+///
+/// # Arguments
+/// * `positions` - Array of positions (x, y pairs)
+/// * `out_accel` - Mutable array of accelerations to add into (x, y pairs)
+/// * `strength` - Force magnitude
+#[wasm_bindgen]
+pub fn cohesion_to_centroid(positions: &[f32], out_accel: &mut [f32], strength: f32) {
+    let mut centroid = [0.0; 2];
+    flock_centroid(positions, &mut centroid);
+
+    for i in 0..positions.len() / 2 {
+        let (px, py) = read_pair(positions, i);
+        let to_x = centroid[0] - px;
+        let to_y = centroid[1] - py;
+        let len = (to_x * to_x + to_y * to_y).sqrt();
+
+        if len > 0.0 {
+            let scale = strength / len;
+            add_pair(out_accel, i, to_x * scale, to_y * scale);
+        }
+    }
+}
+
+/// Relax each boid's velocity toward the mean of its neighbors.
+///
+/// Unlike [`compute_alignment`], this sets velocities directly:
+/// `v += (neighbor_mean - v) * factor`, so 1.0 copies the local mean. All
+/// boids are updated from the velocities as they were before the call.
+/// Isolated boids are unchanged.
+///
+/// # Arguments
+/// * `positions` - Array of positions (x, y pairs)
+/// * `velocities` - Mutable array of velocities (x, y pairs)
+/// * `radius` - Neighbor radius
+/// * `factor` - Blend factor toward the neighbor mean
+#[wasm_bindgen]
+pub fn relax_velocities(positions: &[f32], velocities: &mut [f32], radius: f32, factor: f32) {
+    let count = (positions.len() / 2).min(velocities.len() / 2);
+    let radius_sq = radius * radius;
+    let previous = velocities[..count * 2].to_vec();
+
+    for i in 0..count {
+        let (px, py) = read_pair(positions, i);
+        let mut sum_x = 0.0;
+        let mut sum_y = 0.0;
+        let mut neighbors = 0u32;
+
+        for j in 0..count {
+            let (qx, qy) = read_pair(positions, j);
+            if j != i && (px - qx) * (px - qx) + (py - qy) * (py - qy) < radius_sq {
+                let (vx, vy) = read_pair(&previous, j);
+                sum_x += vx;
+                sum_y += vy;
+                neighbors += 1;
+            }
+        }
+
+        if neighbors > 0 {
+            let inv = 1.0 / neighbors as f32;
+            let (vx, vy) = read_pair(&previous, i);
+            velocities[i * 2] = vx + (sum_x * inv - vx) * factor;
+            velocities[i * 2 + 1] = vy + (sum_y * inv - vy) * factor;
+        }
+    }
+}
+
+/// [`compute_separation`] restricted to each boid's field of view.
+///
+/// A neighbor is ignored when the cosine of the angle between the boid's
+/// heading and the direction to the neighbor is below `fov_cos` (e.g. `0.0`
+/// for a 180° cone). Boids at (near) rest see all around.
+#[wasm_bindgen]
+pub fn compute_separation_fov(
+    positions: &[f32],
+    velocities: &[f32],
+    out_accel: &mut [f32],
+    radius: f32,
+    strength: f32,
+    fov_cos: f32,
+) {
+    let count = positions.len() / 2;
+
+    for i in 0..count {
+        let visible = (0..count).filter(|&j| in_view(positions, velocities, i, j, fov_cos));
+        let (fx, fy) = separation_for(positions, i, visible, radius);
+        add_pair(out_accel, i, fx * strength, fy * strength);
+    }
+}
+
+/// [`compute_alignment`] restricted to each boid's field of view; see
+/// [`compute_separation_fov`].
+#[wasm_bindgen]
+pub fn compute_alignment_fov(
+    positions: &[f32],
+    velocities: &[f32],
+    out_accel: &mut [f32],
+    radius: f32,
+    strength: f32,
+    fov_cos: f32,
+) {
+    let count = positions.len() / 2;
+
+    for i in 0..count {
+        let visible = (0..count).filter(|&j| in_view(positions, velocities, i, j, fov_cos));
+        let (fx, fy) = alignment_for(positions, velocities, i, visible, radius, uniform);
+        add_pair(out_accel, i, fx * strength, fy * strength);
+    }
+}
+
+/// [`compute_cohesion`] restricted to each boid's field of view; see
+/// [`compute_separation_fov`].
+#[wasm_bindgen]
+pub fn compute_cohesion_fov(
+    positions: &[f32],
+    velocities: &[f32],
+    out_accel: &mut [f32],
+    radius: f32,
+    strength: f32,
+    fov_cos: f32,
+) {
+    let count = positions.len() / 2;
+
+    for i in 0..count {
+        let visible = (0..count).filter(|&j| in_view(positions, velocities, i, j, fov_cos));
+        let (fx, fy, _) = cohesion_for(positions, i, visible, radius, uniform);
+        add_pair(out_accel, i, fx * strength, fy * strength);
+    }
+}
+
+/// Whether boid `j` lies inside boid `i`'s view cone.
+fn in_view(positions: &[f32], velocities: &[f32], i: usize, j: usize, fov_cos: f32) -> bool {
+    let (vx, vy) = read_pair(velocities, i);
+    let speed_sq = vx * vx + vy * vy;
+    if speed_sq < 1e-8 {
+        return true;
+    }
+
+    let (px, py) = read_pair(positions, i);
+    let (qx, qy) = read_pair(positions, j);
+    let dx = qx - px;
+    let dy = qy - py;
+    let dist_sq = dx * dx + dy * dy;
+    if dist_sq <= 0.0 {
+        return true;
+    }
+
+    (vx * dx + vy * dy) >= fov_cos * (speed_sq * dist_sq).sqrt()
+}
+
+/// Boids within `radius` of boid `i` from the grid cells around it,
+/// stopping after `max_neighbors` of them when set.
+fn grid_neighbors<'a>(
+    positions: &'a [f32],
+    grid: &'a SpatialGrid,
+    i: usize,
+    radius: f32,
+    max_neighbors: Option<u32>,
+) -> impl Iterator<Item = usize> + 'a {
+    let (px, py) = read_pair(positions, i);
+    let radius_sq = radius * radius;
+    let cap = max_neighbors.map_or(usize::MAX, |n| n as usize);
+
+    grid.candidates(px, py, radius)
+        .filter(move |&j| {
+            let (qx, qy) = read_pair(positions, j);
+            j != i && (px - qx) * (px - qx) + (py - qy) * (py - qy) < radius_sq
+        })
+        .take(cap)
+}
+
+/// Call `f(i, j, dx, dy, dist_sq)` once for every pair of boids `i < j`
+/// closer than `radius`, with `(dx, dy)` the offset from `j` to `i`.
+fn for_each_pair(
+    positions: &[f32],
+    grid: &SpatialGrid,
+    radius: f32,
+    mut f: impl FnMut(usize, usize, f32, f32, f32),
+) {
+    let count = positions.len() / 2;
+    let radius_sq = radius * radius;
+
+    for i in 0..count {
+        let (px, py) = read_pair(positions, i);
+        for j in grid.candidates(px, py, radius) {
+            if j <= i || j >= count {
+                continue;
+            }
+            let (qx, qy) = read_pair(positions, j);
+            let dx = px - qx;
+            let dy = py - qy;
+            let dist_sq = dx * dx + dy * dy;
+
+            if dist_sq < radius_sq {
+                f(i, j, dx, dy, dist_sq);
+            }
+        }
+    }
+}
+
+/// Unscaled separation for boid `i` over the given candidate neighbors.
+fn separation_for(
+    positions: &[f32],
+    i: usize,
+    candidates: impl Iterator<Item = usize>,
+    radius: f32,
+) -> (f32, f32) {
+    separation_with(
+        positions,
+        i,
+        candidates,
+        radius,
+        |dx, dy| (dx, dy),
+        inverse_distance,
+    )
+}
+
+/// Generalized [`separation_for`].
+///
+/// Each raw offset is passed through `delta` first (e.g. to take the
+/// shortest path across wrapped edges), then scaled by `weight(dist_sq)`.
+fn separation_with(
+    positions: &[f32],
+    i: usize,
+    candidates: impl Iterator<Item = usize>,
+    radius: f32,
+    delta: impl Fn(f32, f32) -> (f32, f32),
+    weight: impl Fn(f32) -> f32,
+) -> (f32, f32) {
+    let (px, py) = read_pair(positions, i);
+    let radius_sq = radius * radius;
+    let mut fx = 0.0;
+    let mut fy = 0.0;
+
+    for j in candidates {
+        if j == i {
+            continue;
+        }
+
+        let (qx, qy) = read_pair(positions, j);
+        let (dx, dy) = delta(px - qx, py - qy);
+        let dist_sq = dx * dx + dy * dy;
+
+        if dist_sq > 0.0 && dist_sq < radius_sq {
+            let w = weight(dist_sq);
+            fx += dx * w;
+            fy += dy * w;
+        }
+    }
+
+    (fx, fy)
+}
+
+/// Classic separation weight: unit direction (d / |d|) scaled by 1 / |d|.
+fn inverse_distance(dist_sq: f32) -> f32 {
+    1.0 / dist_sq
+}
+
+/// Equal weight for every neighbor.
+fn uniform(_dist_sq: f32) -> f32 {
+    1.0
+}
+
+/// Weight of a neighbor at squared distance `dist_sq` under `falloff`.
+///
+/// Unknown values behave like [`FALLOFF_CONSTANT`]. Coincident neighbors are
+/// treated as a tiny distance apart so the inverse curves stay finite.
+fn falloff_weight(falloff: u8, dist_sq: f32, radius: f32) -> f32 {
+    let dist_sq = dist_sq.max(f32::EPSILON);
+    match falloff {
+        FALLOFF_LINEAR => (1.0 - dist_sq.sqrt() / radius).max(0.0),
+        FALLOFF_INVERSE => 1.0 / dist_sq.sqrt(),
+        FALLOFF_INVERSE_SQUARE => 1.0 / dist_sq,
+        FALLOFF_SMOOTHSTEP => {
+            let t = (dist_sq.sqrt() / radius).clamp(0.0, 1.0);
+            1.0 - t * t * (3.0 - 2.0 * t)
+        }
+        _ => 1.0,
+    }
+}
+
+/// Unscaled alignment steering for boid `i` over the given candidates,
+/// with each neighbor's velocity weighted by `weight(dist_sq)`.
+fn alignment_for(
+    positions: &[f32],
+    velocities: &[f32],
+    i: usize,
+    candidates: impl Iterator<Item = usize>,
+    radius: f32,
+    weight: impl Fn(f32) -> f32,
+) -> (f32, f32) {
+    let (px, py) = read_pair(positions, i);
+    let radius_sq = radius * radius;
+    let mut sum_vx = 0.0;
+    let mut sum_vy = 0.0;
+    let mut total_weight = 0.0;
+
+    for j in candidates {
+        if j == i {
+            continue;
+        }
+
+        let (qx, qy) = read_pair(positions, j);
+        let dx = px - qx;
+        let dy = py - qy;
+
+        let dist_sq = dx * dx + dy * dy;
+
+        if dist_sq < radius_sq {
+            let (vx, vy) = read_pair(velocities, j);
+            let w = weight(dist_sq);
+            sum_vx += vx * w;
+            sum_vy += vy * w;
+            total_weight += w;
+        }
+    }
+
+    if total_weight <= 0.0 {
+        return (0.0, 0.0);
+    }
+
+    let inv = 1.0 / total_weight;
+    let (vx, vy) = read_pair(velocities, i);
+    (sum_vx * inv - vx, sum_vy * inv - vy)
+}
+
+/// Unit vector from boid `i` toward its neighbors' centroid, weighted by
+/// `weight(dist_sq)`, with the number of neighbors found.
+fn cohesion_for(
+    positions: &[f32],
+    i: usize,
+    candidates: impl Iterator<Item = usize>,
+    radius: f32,
+    weight: impl Fn(f32) -> f32,
+) -> (f32, f32, u32) {
+    let (px, py) = read_pair(positions, i);
+    let radius_sq = radius * radius;
+    let mut sum_x = 0.0;
+    let mut sum_y = 0.0;
+    let mut total_weight = 0.0;
+    let mut neighbors = 0u32;
+
+    for j in candidates {
+        if j == i {
+            continue;
+        }
+
+        let (qx, qy) = read_pair(positions, j);
+        let dx = px - qx;
+        let dy = py - qy;
+
+        let dist_sq = dx * dx + dy * dy;
+
+        if dist_sq < radius_sq {
+            let w = weight(dist_sq);
+            sum_x += qx * w;
+            sum_y += qy * w;
+            total_weight += w;
+            neighbors += 1;
+        }
+    }
+
+    if total_weight <= 0.0 {
+        return (0.0, 0.0, neighbors);
+    }
+
+    let inv = 1.0 / total_weight;
+    let to_x = sum_x * inv - px;
+    let to_y = sum_y * inv - py;
+    let len = (to_x * to_x + to_y * to_y).sqrt();
+
+    if len > 0.0 {
+        (to_x / len, to_y / len, neighbors)
+    } else {
+        (0.0, 0.0, neighbors)
+    }
+}
+
+/// Batch compute species-to-species attraction and repulsion.
+///
+/// Each neighbor within `radius` pulls the boid along the unit direction
+/// toward it, scaled by `matrix[a * species_count + b]`, where `a` is the
+/// boid's species and `b` the neighbor's. Positive coefficients attract,
+/// negative ones repel, and the matrix need not be symmetric. Boids without
+/// a species entry, or whose species is not below `species_count`, neither
+/// feel nor exert these forces.
+///
+/// # Arguments
+/// * `positions` - Array of positions (x, y pairs)
+/// * `species` - Species index per boid
+/// * `out_accel` - Mutable array of accelerations to add into (x, y pairs)
+/// * `matrix` - Row-major `species_count × species_count` coefficients
+/// * `species_count` - Number of species
+/// * `radius` - Neighbor radius
+#[wasm_bindgen]
+pub fn compute_interaction_matrix(
+    positions: &[f32],
+    species: &[u8],
+    out_accel: &mut [f32],
+    matrix: &[f32],
+    species_count: usize,
+    radius: f32,
+) {
+    let count = (positions.len() / 2).min(species.len());
+    let radius_sq = radius * radius;
+    let species_of = |i: usize| Some(species[i] as usize).filter(|&s| s < species_count);
+
+    for i in 0..count {
+        let Some(a) = species_of(i) else {
+            continue;
+        };
+        let (px, py) = read_pair(positions, i);
+        let mut fx = 0.0;
+        let mut fy = 0.0;
+
+        for j in 0..count {
+            let Some(b) = species_of(j) else {
+                continue;
+            };
+            let (qx, qy) = read_pair(positions, j);
+            let dx = qx - px;
+            let dy = qy - py;
+            let dist_sq = dx * dx + dy * dy;
+
+            if j != i && dist_sq > 0.0 && dist_sq < radius_sq {
+                let coefficient = matrix.get(a * species_count + b).copied().unwrap_or(0.0);
+                let scale = coefficient / dist_sq.sqrt();
+                fx += dx * scale;
+                fy += dy * scale;
+            }
+        }
+
+        add_pair(out_accel, i, fx, fy);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_separation_pushes_apart() {
+        let positions = vec![0.0, 0.0, 1.0, 0.0];
+        let mut accel = vec![0.0; 4];
+
+        compute_separation(&positions, &mut accel, 5.0, 2.0, FALLOFF_INVERSE);
+
+        assert!((accel[0] + 2.0).abs() < 0.001);
+        assert!((accel[2] - 2.0).abs() < 0.001);
+        assert!((accel[0] + accel[2]).abs() < 0.001);
+        assert!(accel[1].abs() < 0.001 && accel[3].abs() < 0.001);
+    }
+
+    #[test]
+    fn test_separation_skips_coincident() {
+        let positions = vec![3.0, 3.0, 3.0, 3.0];
+        let mut accel = vec![1.0; 4];
+
+        compute_separation(&positions, &mut accel, 5.0, 1.0, FALLOFF_INVERSE);
+
+        assert_eq!(accel, vec![1.0; 4]);
+    }
+
+    #[test]
+    fn test_separation_wrapped_across_seam() {
+        let positions = vec![2.0, 50.0, 98.0, 50.0];
+        let mut accel = vec![0.0; 4];
+
+        compute_separation_wrapped(&positions, &mut accel, 10.0, 1.0, 100.0, 100.0);
+
+        // Neighbor sits 4 units to the left across the seam
+        assert!(accel[0] > 0.0);
+        assert!(accel[2] < 0.0);
+
+        let mut plain = vec![0.0; 4];
+        compute_separation(&positions, &mut plain, 10.0, 1.0, FALLOFF_INVERSE);
+        assert_eq!(plain, vec![0.0; 4]);
+    }
+
+    #[test]
+    fn test_separation_smooth_bounded() {
+        let positions = vec![0.0, 0.0, 0.001, 0.0, 9.0, 0.0];
+        let mut accel = vec![0.0; 6];
+
+        compute_separation_smooth(&positions, &mut accel, 10.0, 2.0, 4.0);
+
+        // Two neighbors at most, each capped at `strength`
+        for a in &accel {
+            assert!(a.is_finite() && a.abs() <= 4.0);
+        }
+        assert!(accel[0] < 0.0 && accel[2] > 0.0);
+        // Third boid sits near the radius edge and barely moves
+        assert!(accel[4] > 0.0 && accel[4] < 0.5);
+    }
+
+    #[test]
+    fn test_alignment_converges_heading() {
+        let positions = vec![0.0, 0.0, 1.0, 0.0, 0.0, 1.0];
+        let mut velocities = vec![1.0, 0.0, 0.8, 0.6, 0.8, -0.6];
+
+        let spread = |v: &[f32]| {
+            let headings: Vec<f32> = v.chunks(2).map(|p| p[1].atan2(p[0])).collect();
+            let max = headings.iter().cloned().fold(f32::MIN, f32::max);
+            let min = headings.iter().cloned().fold(f32::MAX, f32::min);
+            max - min
+        };
+        let initial = spread(&velocities);
+
+        for _ in 0..10 {
+            let mut accel = vec![0.0; 6];
+            compute_alignment(
+                &positions,
+                &velocities,
+                &mut accel,
+                5.0,
+                0.5,
+                FALLOFF_CONSTANT,
+            );
+            for (v, a) in velocities.iter_mut().zip(&accel) {
+                *v += a;
+            }
+        }
+
+        assert!(spread(&velocities) < initial * 0.01);
+    }
+
+    #[test]
+    fn test_alignment_isolated_boid() {
+        let positions = vec![0.0, 0.0, 100.0, 100.0];
+        let velocities = vec![1.0, 0.0, 0.0, 1.0];
+        let mut accel = vec![0.0; 4];
+
+        compute_alignment(
+            &positions,
+            &velocities,
+            &mut accel,
+            5.0,
+            1.0,
+            FALLOFF_CONSTANT,
+        );
+
+        assert_eq!(accel, vec![0.0; 4]);
+    }
+
+    #[test]
+    fn test_cohesion_toward_cluster() {
+        // Cluster centered on (10, 10), outlier at (4, 10)
+        let positions = vec![10.0, 9.0, 10.0, 11.0, 9.0, 10.0, 11.0, 10.0, 4.0, 10.0];
+        let mut accel = vec![0.0; 10];
+
+        compute_cohesion(&positions, &mut accel, 10.0, 1.5, false, FALLOFF_CONSTANT);
+
+        // Outlier's neighbors are exactly the cluster, so it points at (10, 10)
+        assert!((accel[8] - 1.5).abs() < 0.001);
+        assert!(accel[9].abs() < 0.001);
+    }
+
+    #[test]
+    fn test_cohesion_isolated_boid() {
+        let positions = vec![0.0, 0.0, 100.0, 100.0];
+        let mut accel = vec![0.0; 4];
+
+        compute_cohesion(&positions, &mut accel, 5.0, 1.0, false, FALLOFF_CONSTANT);
+
+        assert_eq!(accel, vec![0.0; 4]);
+    }
+
+    #[test]
+    fn test_cohesion_density_compensation() {
+        let positions: Vec<f32> = (0..50)
+            .flat_map(|k| [10.0 + (k % 7) as f32, 10.0 + (k / 7) as f32])
+            .collect();
+        let mut plain = vec![0.0; 100];
+        let mut compensated = vec![0.0; 100];
+
+        compute_cohesion(&positions, &mut plain, 20.0, 1.0, false, FALLOFF_CONSTANT);
+        compute_cohesion(
+            &positions,
+            &mut compensated,
+            20.0,
+            1.0,
+            true,
+            FALLOFF_CONSTANT,
+        );
+
+        let magnitude = |a: &[f32], i: usize| (a[i * 2].powi(2) + a[i * 2 + 1].powi(2)).sqrt();
+        for i in 0..50 {
+            let m = magnitude(&plain, i);
+            if m > 0.0 {
+                // 49 neighbors each
+                assert!((magnitude(&compensated, i) - m / 7.0).abs() < 1e-5);
+            }
+        }
+    }
+
+    #[test]
+    fn test_cohesion_to_centroid() {
+        let positions = vec![4.0, 0.0, 6.0, 0.0, 5.0, 0.0];
+        let mut accel = vec![0.0; 6];
+
+        cohesion_to_centroid(&positions, &mut accel, 2.0);
+
+        assert_eq!(accel, vec![2.0, 0.0, -2.0, 0.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_relax_velocities_converges() {
+        let positions = vec![0.0, 0.0, 3.0, 0.0, 50.0, 50.0];
+        let mut velocities = vec![2.0, 1.0, -2.0, -1.0, 1.0, 0.0];
+
+        for _ in 0..5 {
+            relax_velocities(&positions, &mut velocities, 5.0, 0.4);
+        }
+
+        // Each pass shrinks the pair's difference by 1 - 2 * 0.4
+        assert!(velocities[..4].iter().all(|v| v.abs() < 0.01));
+        assert_eq!(velocities[0], -velocities[2]);
+        assert_eq!(&velocities[4..], &[1.0, 0.0]);
+    }
+
+    #[test]
+    fn test_grid_forces_match_brute_force() {
+        let mut seed = 7u32;
+        let mut next = || {
+            seed = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+            (seed >> 8) as f32 / (1u32 << 24) as f32
+        };
+        let positions: Vec<f32> = (0..400).map(|_| next() * 200.0).collect();
+        let velocities: Vec<f32> = (0..400).map(|_| next() * 2.0 - 1.0).collect();
+
+        let mut grid = SpatialGrid::new(20.0);
+        grid.rebuild(&positions, 200.0, 200.0);
+        let mut scratch = Scratch::new(200);
+
+        let mut brute = vec![0.0; 400];
+        let mut fast = vec![0.0; 400];
+        compute_separation(&positions, &mut brute, 20.0, 1.0, FALLOFF_INVERSE);
+        compute_alignment(
+            &positions,
+            &velocities,
+            &mut brute,
+            20.0,
+            1.0,
+            FALLOFF_CONSTANT,
+        );
+        compute_cohesion(&positions, &mut brute, 20.0, 1.0, false, FALLOFF_CONSTANT);
+        compute_separation_grid(&positions, &grid, &mut fast, 20.0, 1.0, None, &mut scratch);
+        compute_alignment_grid(
+            &positions,
+            &velocities,
+            &grid,
+            &mut fast,
+            20.0,
+            1.0,
+            None,
+            &mut scratch,
+        );
+        compute_cohesion_grid(&positions, &grid, &mut fast, 20.0, 1.0, None, &mut scratch);
+
+        for (b, f) in brute.iter().zip(&fast) {
+            assert!((b - f).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn test_grid_max_neighbors_cap() {
+        // Boid 0 at the origin with six neighbors one unit away along +x
+        let mut positions = vec![0.0, 0.0];
+        for k in 0..6 {
+            positions.extend_from_slice(&[1.0, k as f32 * 0.01]);
+        }
+        let mut grid = SpatialGrid::new(10.0);
+        grid.rebuild(&positions, 10.0, 10.0);
+        let mut scratch = Scratch::new(7);
+
+        let mut capped = vec![0.0; positions.len()];
+        let mut full = vec![0.0; positions.len()];
+        compute_cohesion_grid(
+            &positions,
+            &grid,
+            &mut capped,
+            5.0,
+            1.0,
+            Some(3),
+            &mut scratch,
+        );
+        compute_cohesion_grid(&positions, &grid, &mut full, 5.0, 1.0, None, &mut scratch);
+
+        let considered: Vec<usize> = grid_neighbors(&positions, &grid, 0, 5.0, Some(3)).collect();
+        assert_eq!(considered.len(), 3);
+
+        // Centroid of only the first three neighbors
+        let (sx, sy) = considered.iter().fold((0.0, 0.0), |(x, y), &j| {
+            let (qx, qy) = read_pair(&positions, j);
+            (x + qx, y + qy)
+        });
+        let (ex, ey) = (sx / 3.0, sy / 3.0);
+        let len = (ex * ex + ey * ey).sqrt();
+        assert!((capped[0] - ex / len).abs() < 1e-6);
+        assert!((capped[1] - ey / len).abs() < 1e-6);
+        assert!((capped[1] - full[1]).abs() > 1e-6);
+    }
+
+    #[test]
+    fn test_fov_excludes_neighbor_behind() {
+        // Boid 0 heads +x; boid 1 is directly behind it
+        let positions = vec![0.0, 0.0, -1.0, 0.0];
+        let velocities = vec![1.0, 0.0, 0.0, 1.0];
+
+        let mut accel = vec![0.0; 4];
+        compute_alignment_fov(&positions, &velocities, &mut accel, 5.0, 1.0, 0.0);
+        assert_eq!(accel[0..2], [0.0, 0.0]);
+
+        let mut accel = vec![0.0; 4];
+        compute_alignment(
+            &positions,
+            &velocities,
+            &mut accel,
+            5.0,
+            1.0,
+            FALLOFF_CONSTANT,
+        );
+        assert!(accel[1] > 0.0);
+
+        // Boid 1 heads +y, so boid 0 is at its side and still visible
+        let mut accel = vec![0.0; 4];
+        compute_separation_fov(&positions, &velocities, &mut accel, 5.0, 1.0, 0.0);
+        assert_eq!(accel[0..2], [0.0, 0.0]);
+        assert!(accel[2] < 0.0);
+    }
+
+    #[test]
+    fn test_falloff_weights_mid_range_less() {
+        // Boid 0 sees one neighbor at half the radius
+        let positions = vec![0.0, 0.0, 5.0, 0.0];
+        let push = |falloff| {
+            let mut accel = vec![0.0; 4];
+            compute_separation(&positions, &mut accel, 10.0, 1.0, falloff);
+            -accel[0]
+        };
+
+        assert!((push(FALLOFF_CONSTANT) - 1.0).abs() < 1e-6);
+        assert!((push(FALLOFF_LINEAR) - 0.5).abs() < 1e-6);
+        assert!((push(FALLOFF_INVERSE_SQUARE) - 0.04).abs() < 1e-6);
+        assert!(push(FALLOFF_INVERSE_SQUARE) < push(FALLOFF_CONSTANT));
+        assert!((push(FALLOFF_SMOOTHSTEP) - 0.5).abs() < 1e-6);
+
+        // A lone neighbor's weight cancels out of the weighted average
+        let velocities = vec![0.0, 0.0, 2.0, 0.0];
+        let mut constant = vec![0.0; 4];
+        let mut inverse_sq = vec![0.0; 4];
+        compute_alignment(
+            &positions,
+            &velocities,
+            &mut constant,
+            10.0,
+            1.0,
+            FALLOFF_CONSTANT,
+        );
+        compute_alignment(
+            &positions,
+            &velocities,
+            &mut inverse_sq,
+            10.0,
+            1.0,
+            FALLOFF_INVERSE_SQUARE,
+        );
+        assert_eq!(constant, inverse_sq);
+    }
+
+    #[test]
+    fn test_grid_scratch_reuse_matches_fresh() {
+        let mut seed = 11u32;
+        let mut next = || {
+            seed = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+            (seed >> 8) as f32 / (1u32 << 24) as f32
+        };
+        let mut positions: Vec<f32> = (0..300).map(|_| next() * 150.0).collect();
+        let velocities: Vec<f32> = (0..300).map(|_| next() * 2.0 - 1.0).collect();
+
+        // Starts too small, so the first step also exercises growth
+        let mut reused = Scratch::new(0);
+        let mut grid = SpatialGrid::new(25.0);
+
+        for _ in 0..3 {
+            grid.rebuild(&positions, 150.0, 150.0);
+
+            let step = |scratch: &mut Scratch| {
+                let mut accel = vec![0.0; 300];
+                compute_separation_grid(&positions, &grid, &mut accel, 10.0, 1.1, None, scratch);
+                compute_alignment_grid(
+                    &positions,
+                    &velocities,
+                    &grid,
+                    &mut accel,
+                    25.0,
+                    1.1,
+                    Some(8),
+                    scratch,
+                );
+                compute_cohesion_grid(&positions, &grid, &mut accel, 25.0, 1.0, None, scratch);
+                accel
+            };
+
+            let accel = step(&mut reused);
+            assert_eq!(accel, step(&mut Scratch::new(150)));
+
+            for (p, a) in positions.iter_mut().zip(&accel) {
+                *p = (*p + a).clamp(0.0, 149.0);
+            }
+        }
+        assert_eq!(reused.len(), 150);
+    }
+
+    #[test]
+    fn test_interaction_matrix_asymmetric() {
+        // Species 0 at the origin, species 1 two units to the right
+        let positions = vec![0.0, 0.0, 2.0, 0.0, 50.0, 0.0];
+        let species = vec![0, 1, 1];
+        // 0 is drawn to 1; 1 flees from 0
+        let matrix = vec![0.0, 1.5, -0.5, 0.0];
+        let mut accel = vec![0.0; 6];
+
+        compute_interaction_matrix(&positions, &species, &mut accel, &matrix, 2, 10.0);
+
+        assert_eq!(&accel[..2], &[1.5, 0.0]);
+        // Also pushed away from the origin: +x
+        assert_eq!(&accel[2..4], &[0.5, 0.0]);
+        assert_eq!(&accel[4..], &[0.0, 0.0]);
+    }
+}
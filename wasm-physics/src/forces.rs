@@ -0,0 +1,328 @@
+//! Global and environmental forces.
+
+use wasm_bindgen::prelude::*;
+
+use crate::rng::next_gaussian;
+use crate::{add_pair, fallback_heading, read_pair};
+
+/// Batch apply softened gravitational attraction toward point masses.
+///
+/// Each attractor pulls with magnitude `g * m / (r² + softening²)`. The
+/// softening term keeps the force finite when a boid sits on an attractor,
+/// where the direction is undefined and no force is applied.
+///
+/// # Arguments
+/// * `positions` - Array of positions (x, y pairs)
+/// * `out_accel` - Mutable array of accelerations to add into (x, y pairs)
+/// * `attractors` - Array of attractor positions (x, y pairs)
+/// * `masses` - Mass of each attractor
+/// * `g` - Gravitational constant
+/// * `softening` - Softening length
+#[wasm_bindgen]
+pub fn apply_gravity_all(
+    positions: &[f32],
+    out_accel: &mut [f32],
+    attractors: &[f32],
+    masses: &[f32],
+    g: f32,
+    softening: f32,
+) {
+    let attractor_count = (attractors.len() / 2).min(masses.len());
+    let soft_sq = softening * softening;
+
+    for i in 0..positions.len() / 2 {
+        let (px, py) = read_pair(positions, i);
+        let mut fx = 0.0;
+        let mut fy = 0.0;
+
+        for (j, &m) in masses.iter().enumerate().take(attractor_count) {
+            let (ax, ay) = read_pair(attractors, j);
+            let dx = ax - px;
+            let dy = ay - py;
+            let dist_sq = dx * dx + dy * dy;
+
+            if dist_sq > 0.0 {
+                let magnitude = g * m / (dist_sq + soft_sq);
+                let dist = dist_sq.sqrt();
+                fx += dx / dist * magnitude;
+                fy += dy / dist * magnitude;
+            }
+        }
+
+        add_pair(out_accel, i, fx, fy);
+    }
+}
+
+/// Mass substituted for zero, negative or non-finite masses, so such boids
+/// barely respond to forces instead of producing infinite accelerations.
+const FALLBACK_MASS: f32 = 1.0e6;
+
+/// Batch convert per-boid forces into accelerations (`force / mass`).
+///
+/// Boids without a mass entry use a mass of 1.
+///
+/// # Arguments
+/// * `forces` - Array of forces (x, y pairs)
+/// * `masses` - Mass of each boid
+/// * `out_accel` - Mutable array of accelerations to add into (x, y pairs)
+#[wasm_bindgen]
+pub fn apply_forces_with_mass(forces: &[f32], masses: &[f32], out_accel: &mut [f32]) {
+    for i in 0..forces.len() / 2 {
+        let mass = match masses.get(i).copied().unwrap_or(1.0) {
+            m if m > 0.0 && m.is_finite() => m,
+            _ => FALLBACK_MASS,
+        };
+        let (fx, fy) = read_pair(forces, i);
+        add_pair(out_accel, i, fx / mass, fy / mass);
+    }
+}
+
+/// Batch sample a 2D flow field and add it as a force.
+///
+/// The field holds one vector per cell, row-major, with each vector located
+/// at its cell's center. Each boid bilinearly blends the four surrounding
+/// vectors; positions past the field's edges use the edge cells.
+///
+/// # Arguments
+/// * `positions` - Array of positions (x, y pairs)
+/// * `out_accel` - Mutable array of accelerations to add into (x, y pairs)
+/// * `field` - Flow vectors (x, y pairs), `field_cols * field_rows` of them
+/// * `field_cols` - Number of field columns
+/// * `field_rows` - Number of field rows
+/// * `cell_size` - World-space size of one field cell
+/// * `strength` - Force multiplier
+#[wasm_bindgen]
+pub fn apply_flow_field(
+    positions: &[f32],
+    out_accel: &mut [f32],
+    field: &[f32],
+    field_cols: usize,
+    field_rows: usize,
+    cell_size: f32,
+    strength: f32,
+) {
+    if field_cols == 0 || field_rows == 0 || cell_size <= 0.0 {
+        return;
+    }
+
+    let max_x = (field_cols - 1) as f32;
+    let max_y = (field_rows - 1) as f32;
+    let cell = |c: usize, r: usize| read_pair(field, r * field_cols + c);
+
+    for i in 0..positions.len() / 2 {
+        let (px, py) = read_pair(positions, i);
+        let gx = (px / cell_size - 0.5).clamp(0.0, max_x);
+        let gy = (py / cell_size - 0.5).clamp(0.0, max_y);
+
+        let c0 = gx as usize;
+        let r0 = gy as usize;
+        let c1 = (c0 + 1).min(field_cols - 1);
+        let r1 = (r0 + 1).min(field_rows - 1);
+        let tx = gx - c0 as f32;
+        let ty = gy - r0 as f32;
+
+        let lerp =
+            |a: (f32, f32), b: (f32, f32), t: f32| (a.0 + (b.0 - a.0) * t, a.1 + (b.1 - a.1) * t);
+        let top = lerp(cell(c0, r0), cell(c1, r0), tx);
+        let bottom = lerp(cell(c0, r1), cell(c1, r1), tx);
+        let (fx, fy) = lerp(top, bottom, ty);
+
+        add_pair(out_accel, i, fx * strength, fy * strength);
+    }
+}
+
+/// Batch add a random thermal kick to every velocity.
+///
+/// Each component receives approximately Gaussian noise with variance
+/// `temperature`, for random-walk diffusion. `rng_state` holds one xorshift
+/// state per boid; boids without a state are left unchanged.
+///
+/// # Arguments
+/// * `velocities` - Mutable array of velocities (x, y pairs)
+/// * `rng_state` - Mutable per-boid PRNG state
+/// * `temperature` - Noise variance per component
+#[wasm_bindgen]
+pub fn apply_thermal_noise(velocities: &mut [f32], rng_state: &mut [u32], temperature: f32) {
+    let scale = temperature.max(0.0).sqrt();
+
+    for (v, state) in velocities.chunks_exact_mut(2).zip(rng_state.iter_mut()) {
+        v[0] += next_gaussian(state) * scale;
+        v[1] += next_gaussian(state) * scale;
+    }
+}
+
+/// Weighted sum of up to three force buffers.
+///
+/// Writes `weight_a * a[i] + weight_b * b[i] + weight_c * c[i]` into `out`,
+/// overwriting it. wasm_bindgen cannot take a slice of slices, hence the
+/// fixed arity; pass an empty buffer for unused slots. Entries missing from
+/// a shorter buffer count as zero.
+///
+/// # Arguments
+/// * `out` - Output array of forces (x, y pairs)
+/// * `a`, `b`, `c` - Input force buffers (x, y pairs)
+/// * `weight_a`, `weight_b`, `weight_c` - Weight of each buffer
+#[wasm_bindgen]
+pub fn blend_forces(
+    out: &mut [f32],
+    a: &[f32],
+    b: &[f32],
+    c: &[f32],
+    weight_a: f32,
+    weight_b: f32,
+    weight_c: f32,
+) {
+    let at = |buf: &[f32], i: usize| buf.get(i).copied().unwrap_or(0.0);
+
+    for (i, o) in out.iter_mut().enumerate() {
+        *o = at(a, i) * weight_a + at(b, i) * weight_b + at(c, i) * weight_c;
+    }
+}
+
+/// Batch add an outward velocity kick around a point, for explosions.
+///
+/// Boids within `radius` of the center get `strength * (1 - dist / radius)`
+/// added to their velocity, pointing away from the center; the kick fades to
+/// zero at the edge. Boids exactly on the center are pushed along
+/// [`fallback_heading`] so the direction stays finite.
+///
+/// # Arguments
+/// * `positions` - Array of positions (x, y pairs)
+/// * `velocities` - Mutable array of velocities (x, y pairs)
+/// * `center_x` - Impulse center X
+/// * `center_y` - Impulse center Y
+/// * `radius` - Radius of effect
+/// * `strength` - Speed added at the center
+#[wasm_bindgen]
+pub fn apply_radial_impulse(
+    positions: &[f32],
+    velocities: &mut [f32],
+    center_x: f32,
+    center_y: f32,
+    radius: f32,
+    strength: f32,
+) {
+    if radius <= 0.0 {
+        return;
+    }
+    let radius_sq = radius * radius;
+
+    for i in 0..positions.len() / 2 {
+        let (px, py) = read_pair(positions, i);
+        let dx = px - center_x;
+        let dy = py - center_y;
+        let dist_sq = dx * dx + dy * dy;
+
+        if dist_sq >= radius_sq {
+            continue;
+        }
+
+        let dist = dist_sq.sqrt();
+        let (nx, ny) = if dist > 0.0 {
+            (dx / dist, dy / dist)
+        } else {
+            fallback_heading(i)
+        };
+        let kick = strength * (1.0 - dist / radius);
+        add_pair(velocities, i, nx * kick, ny * kick);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gravity_pulls_and_stays_finite() {
+        let positions = vec![10.0, 0.0, 0.0, 0.0];
+        let mut accel = vec![0.0; 4];
+
+        apply_gravity_all(&positions, &mut accel, &[0.0, 0.0], &[5.0], 2.0, 1.0);
+
+        // 2 * 5 / (100 + 1) toward the origin
+        assert!((accel[0] + 10.0 / 101.0).abs() < 1e-6);
+        assert_eq!(accel[1], 0.0);
+        assert!(accel[2].is_finite() && accel[3].is_finite());
+    }
+
+    #[test]
+    fn test_forces_with_mass() {
+        let forces = vec![4.0, 0.0, 4.0, 0.0];
+        let mut accel = vec![0.0; 4];
+
+        apply_forces_with_mass(&forces, &[2.0, 0.0], &mut accel);
+
+        assert_eq!(&accel[..2], &[2.0, 0.0]);
+        // Zero mass stays finite and nearly immovable
+        assert!(accel[2].is_finite() && accel[2] < 1e-3);
+    }
+
+    #[test]
+    fn test_flow_field_uniform() {
+        // 3x2 field pointing right
+        let field = [1.0, 0.0].repeat(6);
+        let positions = vec![0.0, 0.0, 15.0, 7.0, 29.0, 19.0, -50.0, 500.0];
+        let mut accel = vec![0.0; 8];
+
+        apply_flow_field(&positions, &mut accel, &field, 3, 2, 10.0, 0.5);
+
+        for i in 0..4 {
+            assert!((accel[i * 2] - 0.5).abs() < 1e-6);
+            assert_eq!(accel[i * 2 + 1], 0.0);
+        }
+    }
+
+    #[test]
+    fn test_thermal_noise_statistics() {
+        let kicks = |temperature: f32| -> (f32, f32) {
+            let mut velocities = vec![0.0; 20_000];
+            let mut states: Vec<u32> = (1..=10_000u32)
+                .map(|i| i.wrapping_mul(0x9E37_79B9))
+                .collect();
+            apply_thermal_noise(&mut velocities, &mut states, temperature);
+
+            let n = velocities.len() as f32;
+            let mean = velocities.iter().sum::<f32>() / n;
+            let var = velocities
+                .iter()
+                .map(|v| (v - mean) * (v - mean))
+                .sum::<f32>()
+                / n;
+            (mean, var)
+        };
+
+        let (mean_1, var_1) = kicks(1.0);
+        let (mean_4, var_4) = kicks(4.0);
+
+        assert!(mean_1.abs() < 0.05 && mean_4.abs() < 0.1);
+        assert!((var_1 - 1.0).abs() < 0.1);
+        assert!((var_4 / var_1 - 4.0).abs() < 0.4);
+    }
+
+    #[test]
+    fn test_blend_forces_average() {
+        let a = vec![2.0, 0.0, 4.0, -2.0];
+        let b = vec![0.0, 2.0, 0.0, 6.0];
+        let mut out = vec![9.0; 4];
+
+        blend_forces(&mut out, &a, &b, &[], 0.5, 0.5, 1.0);
+
+        assert_eq!(out, vec![1.0, 1.0, 2.0, 2.0]);
+    }
+
+    #[test]
+    fn test_radial_impulse() {
+        let positions = vec![12.0, 10.0, 10.0, 10.0, 50.0, 10.0];
+        let mut velocities = vec![0.0; 6];
+
+        apply_radial_impulse(&positions, &mut velocities, 10.0, 10.0, 4.0, 8.0);
+
+        // Half way to the edge: half strength, pointing away
+        assert!((velocities[0] - 4.0).abs() < 1e-6);
+        assert_eq!(velocities[1], 0.0);
+        // On the center: full strength in a finite direction
+        let kick = (velocities[2] * velocities[2] + velocities[3] * velocities[3]).sqrt();
+        assert!((kick - 8.0).abs() < 1e-5);
+        assert_eq!(&velocities[4..], &[0.0, 0.0]);
+    }
+}
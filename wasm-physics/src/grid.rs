@@ -0,0 +1,457 @@
+//! Uniform spatial grid for neighbor queries.
+//!
+//! Boids are bucketed into square cells of `cell_size`, so a radius query only
+//! has to scan the cells overlapping the query circle instead of every boid.
+
+use wasm_bindgen::prelude::*;
+
+use crate::read_pair;
+
+/// Uniform bucket grid over the world rectangle.
+///
+/// Positions outside the world are clamped into the edge cells, so queries
+/// stay exact for boids that have drifted past the bounds.
+#[wasm_bindgen]
+pub struct SpatialGrid {
+    cell_size: f32,
+    cols: usize,
+    rows: usize,
+    cells: Vec<Vec<u32>>,
+    positions: Vec<f32>,
+    boid_cells: Vec<usize>,
+}
+
+#[wasm_bindgen]
+impl SpatialGrid {
+    /// Create an empty grid with the given cell size.
+    ///
+    /// # Arguments
+    /// * `cell_size` - Cell edge length, ideally the largest query radius
+    #[wasm_bindgen(constructor)]
+    pub fn new(cell_size: f32) -> SpatialGrid {
+        SpatialGrid {
+            cell_size: cell_size.max(f32::EPSILON),
+            cols: 1,
+            rows: 1,
+            cells: vec![Vec::new()],
+            positions: Vec::new(),
+            boid_cells: Vec::new(),
+        }
+    }
+
+    /// Create a grid pre-sized for a known world and flock size.
+    ///
+    /// Each cell reserves room for the average number of boids per cell
+    /// (plus slack for clustering), so the first rebuilds don't grow the
+    /// buckets one push at a time.
+    ///
+    /// # Arguments
+    /// * `cell_size` - Cell edge length, ideally the largest query radius
+    /// * `width` - World width
+    /// * `height` - World height
+    /// * `expected_boids` - Expected number of boids
+    pub fn with_capacity(
+        cell_size: f32,
+        width: f32,
+        height: f32,
+        expected_boids: usize,
+    ) -> SpatialGrid {
+        let mut grid = SpatialGrid::new(cell_size);
+        grid.resize(width, height);
+
+        let per_cell = (expected_boids * 2).div_ceil(grid.cells.len());
+        for cell in grid.cells.iter_mut() {
+            cell.reserve(per_cell);
+        }
+        grid.positions.reserve(expected_boids * 2);
+        grid.boid_cells.reserve(expected_boids);
+        grid
+    }
+
+    /// Cell edge length.
+    #[wasm_bindgen(getter)]
+    pub fn cell_size(&self) -> f32 {
+        self.cell_size
+    }
+
+    /// Rebucket all boids.
+    ///
+    /// # Arguments
+    /// * `positions` - Array of positions (x, y pairs)
+    /// * `width` - World width
+    /// * `height` - World height
+    pub fn rebuild(&mut self, positions: &[f32], width: f32, height: f32) {
+        self.resize(width, height);
+        self.rebucket(positions);
+    }
+
+    /// Move only the boids whose cell changed since the last rebuild/update.
+    ///
+    /// Keeps the world size from the last `rebuild`. Cheaper than a full
+    /// rebuild when boids move little between frames; if the boid count
+    /// changed, all boids are rebucketed.
+    ///
+    /// # Arguments
+    /// * `positions` - Array of positions (x, y pairs)
+    pub fn update(&mut self, positions: &[f32]) {
+        if positions.len() / 2 != self.boid_cells.len() {
+            self.rebucket(positions);
+            return;
+        }
+
+        for i in 0..self.boid_cells.len() {
+            let (x, y) = read_pair(positions, i);
+            let cell = self.cell_index(x, y);
+            let old = self.boid_cells[i];
+
+            if cell != old {
+                let items = &mut self.cells[old];
+                if let Some(k) = items.iter().position(|&j| j as usize == i) {
+                    items.swap_remove(k);
+                }
+                self.cells[cell].push(i as u32);
+                self.boid_cells[i] = cell;
+            }
+        }
+
+        self.positions.clear();
+        self.positions.extend_from_slice(positions);
+    }
+
+    /// Indices of boids within `radius` of boid `idx`, excluding itself.
+    ///
+    /// Uses the positions from the last `rebuild`. When `radius` is at most
+    /// `cell_size` only the 3×3 block of cells around the boid is scanned.
+    /// An `idx` past the last boid has no neighbors.
+    pub fn query_neighbors(&self, idx: usize, radius: f32) -> Vec<u32> {
+        if idx >= self.positions.len() / 2 {
+            return Vec::new();
+        }
+
+        self.neighbors(&self.positions, idx, radius)
+            .map(|(j, _)| j as u32)
+            .collect()
+    }
+
+    /// Like [`query_neighbors`](Self::query_neighbors), also writing each
+    /// neighbor's squared distance to boid `idx`.
+    ///
+    /// Writes neighbor `n` to `out_indices[n]` and its squared distance to
+    /// `out_dist_sq[n]`, stopping when either array is full. Returns how many
+    /// neighbors were written. Distances are measured on `positions`, which
+    /// should be the array the grid was last rebuilt from.
+    ///
+    /// # Arguments
+    /// * `positions` - Array of positions (x, y pairs)
+    /// * `idx` - Index of the query boid
+    /// * `radius` - Neighbor radius
+    /// * `out_indices` - Output array of boid indices
+    /// * `out_dist_sq` - Output array of squared distances
+    pub fn query_neighbors_with_dist(
+        &self,
+        positions: &[f32],
+        idx: usize,
+        radius: f32,
+        out_indices: &mut [u32],
+        out_dist_sq: &mut [f32],
+    ) -> usize {
+        if idx >= positions.len() / 2 {
+            return 0;
+        }
+
+        let slots = out_indices.iter_mut().zip(out_dist_sq.iter_mut());
+        let mut written = 0;
+        for ((o, d), (j, dist_sq)) in slots.zip(self.neighbors(positions, idx, radius)) {
+            *o = j as u32;
+            *d = dist_sq;
+            written += 1;
+        }
+        written
+    }
+
+    /// Write the indices of the `k` boids nearest to boid `idx` into `out`,
+    /// closest first, excluding the boid itself.
+    ///
+    /// Scans rings of cells outward from the boid's cell until no unscanned
+    /// cell can hold anything closer than the k-th best so far. Returns how
+    /// many indices were written: at most `min(k, out.len())`, fewer if the
+    /// flock is smaller. Distances are measured on `positions`, which should
+    /// be the array the grid was last rebuilt from; ties go to the lower
+    /// index.
+    ///
+    /// # Arguments
+    /// * `positions` - Array of positions (x, y pairs)
+    /// * `idx` - Index of the query boid
+    /// * `k` - Number of neighbors wanted
+    /// * `out` - Output array of boid indices
+    pub fn k_nearest(&self, positions: &[f32], idx: usize, k: usize, out: &mut [u32]) -> usize {
+        let k = k.min(out.len());
+        if k == 0 || idx >= positions.len() / 2 {
+            return 0;
+        }
+
+        let (px, py) = read_pair(positions, idx);
+        let (col, row) = self.cell_coords(px, py);
+        let mut found: Vec<(f32, usize)> = Vec::new();
+
+        for ring in 0.. {
+            let min_col = col.saturating_sub(ring);
+            let max_col = (col + ring).min(self.cols - 1);
+            let min_row = row.saturating_sub(ring);
+            let max_row = (row + ring).min(self.rows - 1);
+
+            for r in min_row..=max_row {
+                for c in min_col..=max_col {
+                    if c.abs_diff(col).max(r.abs_diff(row)) != ring {
+                        continue;
+                    }
+                    for &j in &self.cells[r * self.cols + c] {
+                        let j = j as usize;
+                        if j != idx {
+                            let (qx, qy) = read_pair(positions, j);
+                            found.push(((px - qx).powi(2) + (py - qy).powi(2), j));
+                        }
+                    }
+                }
+            }
+            found.sort_unstable_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+            found.truncate(k);
+
+            // Anything not yet scanned lies beyond the nearest open side of
+            // the scanned block; sides at the world edge hide nothing.
+            let size = self.cell_size;
+            let gaps = [
+                (min_col > 0, px - min_col as f32 * size),
+                (max_col + 1 < self.cols, (max_col + 1) as f32 * size - px),
+                (min_row > 0, py - min_row as f32 * size),
+                (max_row + 1 < self.rows, (max_row + 1) as f32 * size - py),
+            ];
+            let Some(gap) = gaps
+                .iter()
+                .filter(|(open, _)| *open)
+                .map(|&(_, gap)| gap.max(0.0))
+                .reduce(f32::min)
+            else {
+                break;
+            };
+            if found.len() == k && found[k - 1].0 <= gap * gap {
+                break;
+            }
+        }
+
+        for (o, &(_, j)) in out.iter_mut().zip(&found) {
+            *o = j as u32;
+        }
+        found.len()
+    }
+}
+
+impl SpatialGrid {
+    /// Set the cell layout for a `width × height` world, keeping the
+    /// buckets (and their capacity) that still exist.
+    fn resize(&mut self, width: f32, height: f32) {
+        self.cols = ((width / self.cell_size).ceil() as usize).max(1);
+        self.rows = ((height / self.cell_size).ceil() as usize).max(1);
+
+        let cell_count = self.cols * self.rows;
+        self.cells.resize_with(cell_count, Vec::new);
+        self.cells.truncate(cell_count);
+    }
+
+    /// Clear every cell and insert all boids at their current cells.
+    fn rebucket(&mut self, positions: &[f32]) {
+        for cell in self.cells.iter_mut() {
+            cell.clear();
+        }
+
+        self.positions.clear();
+        self.positions.extend_from_slice(positions);
+        self.boid_cells.clear();
+
+        for i in 0..positions.len() / 2 {
+            let (x, y) = read_pair(positions, i);
+            let cell = self.cell_index(x, y);
+            self.cells[cell].push(i as u32);
+            self.boid_cells.push(cell);
+        }
+    }
+
+    /// Neighbors of boid `idx` within `radius`, with squared distances.
+    fn neighbors<'a>(
+        &'a self,
+        positions: &'a [f32],
+        idx: usize,
+        radius: f32,
+    ) -> impl Iterator<Item = (usize, f32)> + 'a {
+        let (px, py) = read_pair(positions, idx);
+        let radius_sq = radius * radius;
+
+        self.candidates(px, py, radius).filter_map(move |j| {
+            if j == idx {
+                return None;
+            }
+            let (qx, qy) = read_pair(positions, j);
+            let dx = px - qx;
+            let dy = py - qy;
+            let dist_sq = dx * dx + dy * dy;
+            (dist_sq < radius_sq).then_some((j, dist_sq))
+        })
+    }
+
+    fn cell_coords(&self, x: f32, y: f32) -> (usize, usize) {
+        let col = ((x / self.cell_size).floor() as isize).clamp(0, self.cols as isize - 1);
+        let row = ((y / self.cell_size).floor() as isize).clamp(0, self.rows as isize - 1);
+        (col as usize, row as usize)
+    }
+
+    fn cell_index(&self, x: f32, y: f32) -> usize {
+        let (col, row) = self.cell_coords(x, y);
+        row * self.cols + col
+    }
+
+    /// Every boid bucketed in a cell overlapping the square around (x, y)
+    /// with half-extent `radius`. Callers still need the exact distance test.
+    pub(crate) fn candidates(
+        &self,
+        x: f32,
+        y: f32,
+        radius: f32,
+    ) -> impl Iterator<Item = usize> + '_ {
+        let (min_col, min_row) = self.cell_coords(x - radius, y - radius);
+        let (max_col, max_row) = self.cell_coords(x + radius, y + radius);
+
+        (min_row..=max_row).flat_map(move |row| {
+            (min_col..=max_col).flat_map(move |col| {
+                self.cells[row * self.cols + col]
+                    .iter()
+                    .map(|&j| j as usize)
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grid_matches_brute_force() {
+        let mut seed = 12345u32;
+        let mut next = || {
+            seed = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+            (seed >> 8) as f32 / (1u32 << 24) as f32
+        };
+        let positions: Vec<f32> = (0..2000).map(|_| next() * 500.0).collect();
+
+        let mut grid = SpatialGrid::new(25.0);
+        grid.rebuild(&positions, 500.0, 500.0);
+
+        let radius = 25.0;
+        for i in 0..1000 {
+            let mut from_grid = grid.query_neighbors(i, radius);
+            from_grid.sort_unstable();
+
+            let (px, py) = read_pair(&positions, i);
+            let brute: Vec<u32> = (0..1000)
+                .filter(|&j| {
+                    let (qx, qy) = read_pair(&positions, j);
+                    j != i && (px - qx).powi(2) + (py - qy).powi(2) < radius * radius
+                })
+                .map(|j| j as u32)
+                .collect();
+
+            assert_eq!(from_grid, brute);
+        }
+
+        assert!(grid.query_neighbors(1000, radius).is_empty());
+    }
+
+    #[test]
+    fn test_query_neighbors_with_dist() {
+        let positions = vec![10.0, 10.0, 13.0, 14.0, 8.0, 10.0, 40.0, 40.0];
+        let mut grid = SpatialGrid::new(10.0);
+        grid.rebuild(&positions, 50.0, 50.0);
+
+        let mut indices = [0u32; 4];
+        let mut dist_sq = [0.0f32; 4];
+        let n = grid.query_neighbors_with_dist(&positions, 0, 6.0, &mut indices, &mut dist_sq);
+
+        let mut found: Vec<(u32, f32)> = indices.into_iter().zip(dist_sq).take(n).collect();
+        found.sort_unstable_by_key(|&(j, _)| j);
+        assert_eq!(found, vec![(1, 25.0), (2, 4.0)]);
+
+        // Output is cut off at the shorter array
+        let n = grid.query_neighbors_with_dist(&positions, 0, 6.0, &mut indices, &mut dist_sq[..1]);
+        assert_eq!(n, 1);
+    }
+
+    #[test]
+    fn test_update_matches_rebuild() {
+        let mut positions: Vec<f32> = (0..200).map(|i| (i * 37 % 100) as f32).collect();
+        let mut grid = SpatialGrid::new(10.0);
+        grid.rebuild(&positions, 100.0, 100.0);
+
+        for (i, p) in positions.iter_mut().enumerate() {
+            *p += if i % 3 == 0 { 4.5 } else { -2.0 };
+        }
+        grid.update(&positions);
+
+        let mut fresh = SpatialGrid::new(10.0);
+        fresh.rebuild(&positions, 100.0, 100.0);
+
+        let sorted = |g: &SpatialGrid| -> Vec<Vec<u32>> {
+            g.cells
+                .iter()
+                .map(|c| {
+                    let mut c = c.clone();
+                    c.sort_unstable();
+                    c
+                })
+                .collect()
+        };
+        assert_eq!(sorted(&grid), sorted(&fresh));
+        assert_eq!(
+            grid.query_neighbors(5, 10.0).len(),
+            fresh.query_neighbors(5, 10.0).len()
+        );
+    }
+
+    #[test]
+    fn test_with_capacity_matches_new() {
+        let positions: Vec<f32> = (0..600).map(|i| (i * 53 % 300) as f32).collect();
+
+        let mut sized = SpatialGrid::with_capacity(20.0, 300.0, 300.0, 300);
+        let mut plain = SpatialGrid::new(20.0);
+        assert!(sized.cells.iter().all(|c| c.capacity() >= 3));
+
+        sized.rebuild(&positions, 300.0, 300.0);
+        plain.rebuild(&positions, 300.0, 300.0);
+
+        assert_eq!(sized.cells, plain.cells);
+        for i in 0..300 {
+            assert_eq!(
+                sized.query_neighbors(i, 20.0),
+                plain.query_neighbors(i, 20.0)
+            );
+        }
+    }
+
+    #[test]
+    fn test_k_nearest() {
+        // Boid 0 at (50, 50); 3, 1 and 4 are closest, in that order
+        let positions = vec![
+            50.0, 50.0, 56.0, 50.0, 90.0, 90.0, 50.0, 53.0, 43.0, 44.0, 5.0, 5.0, 70.0, 50.0,
+        ];
+        let mut grid = SpatialGrid::new(10.0);
+        grid.rebuild(&positions, 100.0, 100.0);
+
+        let mut out = [99; 3];
+        assert_eq!(grid.k_nearest(&positions, 0, 3, &mut out), 3);
+        assert_eq!(out, [3, 1, 4]);
+
+        // Asking for more than the flock holds returns everyone else
+        let mut out = [99; 10];
+        assert_eq!(grid.k_nearest(&positions, 0, 10, &mut out), 6);
+        assert_eq!(&out[..6], &[3, 1, 4, 6, 2, 5]);
+    }
+}
@@ -3,8 +3,61 @@
 //! This module provides WebAssembly functions for batch physics operations
 //! using SIMD instructions for improved performance.
 
+use core::sync::atomic::{AtomicBool, Ordering};
+
 use wasm_bindgen::prelude::*;
 
+#[cfg(feature = "bench")]
+mod bench;
+mod bounds;
+mod buffers;
+mod collisions;
+mod double;
+mod flocking;
+mod forces;
+mod grid;
+mod integrate;
+mod obstacles;
+mod params;
+mod quadtree;
+mod render;
+mod rng;
+mod scratch;
+mod simulation;
+mod snapshot;
+mod soa;
+mod sort;
+mod spawn;
+mod stats;
+mod steering;
+mod three_d;
+mod vector;
+
+#[cfg(feature = "bench")]
+pub use bench::*;
+pub use bounds::*;
+pub use buffers::FlockBuffers;
+pub use collisions::*;
+pub use double::*;
+pub use flocking::*;
+pub use forces::*;
+pub use grid::SpatialGrid;
+pub use integrate::*;
+pub use obstacles::*;
+pub use params::SimParams;
+pub use quadtree::QuadTree;
+pub use render::*;
+pub use scratch::Scratch;
+pub use simulation::*;
+pub use snapshot::*;
+pub use soa::*;
+pub use sort::*;
+pub use spawn::*;
+pub use stats::*;
+pub use steering::*;
+pub use three_d::*;
+pub use vector::*;
+
 // Use `wee_alloc` as the global allocator for smaller WASM size
 #[cfg(feature = "wee_alloc")]
 #[global_allocator]
@@ -18,6 +71,64 @@ pub fn init() {
     console_error_panic_hook::set_once();
 }
 
+/// Read the (x, y) pair for boid `i`, treating missing lanes as zero.
+#[inline]
+pub(crate) fn read_pair(values: &[f32], i: usize) -> (f32, f32) {
+    let idx = i * 2;
+    (
+        values.get(idx).copied().unwrap_or(0.0),
+        values.get(idx + 1).copied().unwrap_or(0.0),
+    )
+}
+
+/// Add (x, y) into the pair for boid `i`, skipping missing lanes.
+#[inline]
+pub(crate) fn add_pair(values: &mut [f32], i: usize, x: f32, y: f32) {
+    let idx = i * 2;
+    if let Some(v) = values.get_mut(idx) {
+        *v += x;
+    }
+    if let Some(v) = values.get_mut(idx + 1) {
+        *v += y;
+    }
+}
+
+/// Scale (x, y) down so its magnitude is at most `max`.
+#[inline]
+pub(crate) fn clamp_magnitude(x: f32, y: f32, max: f32) -> (f32, f32) {
+    let len_sq = x * x + y * y;
+    if len_sq > max * max {
+        let scale = max / len_sq.sqrt();
+        (x * scale, y * scale)
+    } else {
+        (x, y)
+    }
+}
+
+/// Shortest signed delta along a wrapped axis of length `size`.
+#[inline]
+pub(crate) fn wrap_delta(d: f32, size: f32) -> f32 {
+    let half = size * 0.5;
+    if d > half {
+        d - size
+    } else if d < -half {
+        d + size
+    } else {
+        d
+    }
+}
+
+/// Deterministic unit heading for a stalled boid, derived from its index.
+///
+/// Consecutive indices are spread by the golden angle so a stalled group
+/// fans out instead of moving in lockstep.
+#[inline]
+pub(crate) fn fallback_heading(i: usize) -> (f32, f32) {
+    const GOLDEN_ANGLE: f32 = 2.399_963;
+    let angle = (i as f32 * GOLDEN_ANGLE) % core::f32::consts::TAU;
+    (angle.cos(), angle.sin())
+}
+
 /// Check if SIMD is supported in this environment.
 /// Returns true if the WASM module was compiled with SIMD support.
 #[wasm_bindgen]
@@ -27,10 +138,58 @@ pub fn simd_supported() -> bool {
     true
 }
 
+/// Squared speed below which a boid counts as stalled.
+///
+/// Stalled boids cannot be rescaled to `min_speed` and restart along
+/// [`fallback_heading`] instead.
+pub(crate) const SPEED_EPSILON: f32 = 0.0001;
+
+/// Bit set in [`feature_flags`] when built with `simd128`.
+pub const FEATURE_SIMD: u32 = 1 << 0;
+/// Bit set in [`feature_flags`] when `wee_alloc` is the global allocator.
+pub const FEATURE_WEE_ALLOC: u32 = 1 << 1;
+/// Bit set in [`feature_flags`] when the console panic hook is installed.
+pub const FEATURE_PANIC_HOOK: u32 = 1 << 2;
+
+/// Squared-speed threshold below which boids are treated as stalled.
+#[wasm_bindgen]
+pub fn epsilon() -> f32 {
+    SPEED_EPSILON
+}
+
+/// Crate version this module was built from.
+#[wasm_bindgen]
+pub fn version() -> String {
+    env!("CARGO_PKG_VERSION").to_string()
+}
+
+/// Bitfield of compile-time features in this build.
+///
+/// Bit 0 = SIMD, bit 1 = `wee_alloc`, bit 2 = console panic hook.
+#[wasm_bindgen]
+pub fn feature_flags() -> u32 {
+    let mut flags = 0;
+    if cfg!(target_feature = "simd128") {
+        flags |= FEATURE_SIMD;
+    }
+    if cfg!(feature = "wee_alloc") {
+        flags |= FEATURE_WEE_ALLOC;
+    }
+    if cfg!(feature = "console_error_panic_hook") {
+        flags |= FEATURE_PANIC_HOOK;
+    }
+    flags
+}
+
 /// Batch integrate positions using velocities and accelerations.
 ///
 /// Arrays are interleaved: [x0, y0, x1, y1, ...]
 ///
+/// Stalled boids are restarted the same way as in [`clamp_speeds_all`].
+///
+/// When compiled with `simd128`, two boids (four lanes) are processed per
+/// iteration and any trailing boid falls through to the scalar loop.
+///
 /// # Arguments
 /// * `positions` - Mutable array of positions (x, y pairs)
 /// * `velocities` - Mutable array of velocities (x, y pairs)
@@ -48,13 +207,298 @@ pub fn integrate_all(
     min_speed: f32,
     max_speed: f32,
     drag: f32,
+) {
+    #[cfg(target_feature = "simd128")]
+    let start = if simd_enabled() {
+        integrate_simd(
+            positions,
+            velocities,
+            accelerations,
+            dt,
+            min_speed,
+            max_speed,
+            drag,
+        )
+    } else {
+        0
+    };
+    #[cfg(not(target_feature = "simd128"))]
+    let start = 0;
+
+    integrate_scalar(
+        positions,
+        velocities,
+        accelerations,
+        start,
+        dt,
+        min_speed,
+        max_speed,
+        drag,
+    );
+}
+
+/// Whether this build has a SIMD path that benefits from aligned buffers.
+///
+/// When true, [`integrate_all`] uses aligned `v128` loads and stores if the
+/// positions, velocities and accelerations all start on a 16-byte boundary,
+/// and unaligned ones otherwise. Results are identical either way; aligning
+/// the typed arrays only affects speed.
+#[wasm_bindgen]
+pub fn needs_alignment() -> bool {
+    cfg!(target_feature = "simd128")
+}
+
+/// Runtime switch for the SIMD paths; see [`set_simd_enabled`].
+static SIMD_ENABLED: AtomicBool = AtomicBool::new(true);
+
+/// Enable or disable the SIMD paths at runtime, for A/B benchmarking.
+///
+/// When disabled, every function with a SIMD path runs its scalar loop
+/// instead, on any data. Has no effect in builds without `simd128`, which
+/// always run the scalar loops.
+///
+/// # Arguments
+/// * `enabled` - Whether to use the SIMD paths
+#[wasm_bindgen]
+pub fn set_simd_enabled(enabled: bool) {
+    SIMD_ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// Whether the SIMD paths are compiled in and currently enabled.
+#[wasm_bindgen]
+pub fn simd_enabled() -> bool {
+    cfg!(target_feature = "simd128") && SIMD_ENABLED.load(Ordering::Relaxed)
+}
+
+/// Validated version of [`integrate_all`].
+///
+/// Fails without touching any array unless positions, velocities and
+/// accelerations all have the same even length. Prefer [`integrate_all`] in
+/// hot paths once the buffers are known to be consistent.
+///
+/// # Arguments
+/// * `positions` - Mutable array of positions (x, y pairs)
+/// * `velocities` - Mutable array of velocities (x, y pairs)
+/// * `accelerations` - Array of accelerations (x, y pairs)
+/// * `dt` - Delta time
+/// * `min_speed` - Minimum speed
+/// * `max_speed` - Maximum speed
+/// * `drag` - Drag coefficient
+#[wasm_bindgen]
+pub fn try_integrate_all(
+    positions: &mut [f32],
+    velocities: &mut [f32],
+    accelerations: &[f32],
+    dt: f32,
+    min_speed: f32,
+    max_speed: f32,
+    drag: f32,
+) -> Result<(), JsValue> {
+    check_lengths(&[
+        ("positions", positions.len()),
+        ("velocities", velocities.len()),
+        ("accelerations", accelerations.len()),
+    ])
+    .map_err(|e| JsValue::from_str(&e))?;
+
+    integrate_all(
+        positions,
+        velocities,
+        accelerations,
+        dt,
+        min_speed,
+        max_speed,
+        drag,
+    );
+    Ok(())
+}
+
+/// [`integrate_all`] restricted to active boids.
+///
+/// Boids with `active[i] == 0` are skipped entirely, so dead boids can stay
+/// in the arrays without being compacted away. Boids beyond the end of
+/// `active` count as active.
+///
+/// Boids flagged in `sleeping` (see [`mark_sleeping`]) are skipped too,
+/// unless a nonzero acceleration acts on them, which wakes them and clears
+/// their flag. Pass an empty array to disable sleeping.
+///
+/// # Arguments
+/// * `positions` - Mutable array of positions (x, y pairs)
+/// * `velocities` - Mutable array of velocities (x, y pairs)
+/// * `accelerations` - Array of accelerations (x, y pairs)
+/// * `active` - Per-boid flag, 0 for inactive
+/// * `sleeping` - Mutable per-boid flag, nonzero for sleeping
+/// * `dt` - Delta time
+/// * `min_speed` - Minimum speed
+/// * `max_speed` - Maximum speed
+/// * `drag` - Drag coefficient
+#[wasm_bindgen]
+#[allow(clippy::too_many_arguments)]
+pub fn integrate_all_masked(
+    positions: &mut [f32],
+    velocities: &mut [f32],
+    accelerations: &[f32],
+    active: &[u8],
+    sleeping: &mut [u8],
+    dt: f32,
+    min_speed: f32,
+    max_speed: f32,
+    drag: f32,
+) {
+    let count = (positions.len() / 2).min(velocities.len() / 2);
+    let drag_factor = 1.0 - drag;
+
+    for i in 0..count {
+        if active.get(i) == Some(&0) {
+            continue;
+        }
+
+        let (ax, ay) = read_pair(accelerations, i);
+        if let Some(flag) = sleeping.get_mut(i).filter(|f| **f != 0) {
+            if ax == 0.0 && ay == 0.0 {
+                continue;
+            }
+            *flag = 0;
+        }
+
+        let (vx, vy) = read_pair(velocities, i);
+        let (vx, vy) = clamp_speed(
+            i,
+            (vx + ax * dt) * drag_factor,
+            (vy + ay * dt) * drag_factor,
+            min_speed,
+            max_speed,
+        );
+
+        velocities[i * 2] = vx;
+        velocities[i * 2 + 1] = vy;
+        positions[i * 2] += vx * dt;
+        positions[i * 2 + 1] += vy * dt;
+    }
+}
+
+/// Batch flag boids that have come to rest.
+///
+/// Writes 1 for boids slower than `speed_threshold` and 0 otherwise. Feed
+/// the flags to [`integrate_all_masked`] to skip settled boids until a force
+/// wakes them.
+///
+/// # Arguments
+/// * `velocities` - Array of velocities (x, y pairs)
+/// * `out_sleeping` - Output flag per boid
+/// * `speed_threshold` - Speed below which a boid sleeps
+#[wasm_bindgen]
+pub fn mark_sleeping(velocities: &[f32], out_sleeping: &mut [u8], speed_threshold: f32) {
+    let threshold_sq = speed_threshold * speed_threshold;
+
+    for (v, out) in velocities.chunks_exact(2).zip(out_sleeping.iter_mut()) {
+        *out = u8::from(v[0] * v[0] + v[1] * v[1] < threshold_sq);
+    }
+}
+
+/// [`integrate_all`] with a limit on how fast each boid can turn.
+///
+/// After the usual velocity update, the new heading is kept within
+/// `max_turn_radians` of the old one; speed is unaffected. Boids starting
+/// from rest can pick any heading. A boid asked to reverse exactly turns
+/// counter-clockwise.
+///
+/// # Arguments
+/// * `positions` - Mutable array of positions (x, y pairs)
+/// * `velocities` - Mutable array of velocities (x, y pairs)
+/// * `accelerations` - Array of accelerations (x, y pairs)
+/// * `dt` - Delta time
+/// * `min_speed` - Minimum speed
+/// * `max_speed` - Maximum speed
+/// * `drag` - Drag coefficient
+/// * `max_turn_radians` - Maximum heading change per step
+#[wasm_bindgen]
+#[allow(clippy::too_many_arguments)]
+pub fn integrate_all_turn_limited(
+    positions: &mut [f32],
+    velocities: &mut [f32],
+    accelerations: &[f32],
+    dt: f32,
+    min_speed: f32,
+    max_speed: f32,
+    drag: f32,
+    max_turn_radians: f32,
+) {
+    let count = (positions.len() / 2).min(velocities.len() / 2);
+    let drag_factor = 1.0 - drag;
+    let max_turn = max_turn_radians.max(0.0);
+
+    for i in 0..count {
+        let (ax, ay) = read_pair(accelerations, i);
+        let (old_x, old_y) = read_pair(velocities, i);
+        let (mut vx, mut vy) = clamp_speed(
+            i,
+            (old_x + ax * dt) * drag_factor,
+            (old_y + ay * dt) * drag_factor,
+            min_speed,
+            max_speed,
+        );
+
+        let old_speed = (old_x * old_x + old_y * old_y).sqrt();
+        if old_speed > 0.0 {
+            let turn = (old_x * vy - old_y * vx).atan2(old_x * vx + old_y * vy);
+            if turn.abs() > max_turn {
+                let speed = (vx * vx + vy * vy).sqrt() / old_speed;
+                let (sin, cos) = (max_turn * turn.signum()).sin_cos();
+                vx = (old_x * cos - old_y * sin) * speed;
+                vy = (old_x * sin + old_y * cos) * speed;
+            }
+        }
+
+        velocities[i * 2] = vx;
+        velocities[i * 2 + 1] = vy;
+        positions[i * 2] += vx * dt;
+        positions[i * 2 + 1] += vy * dt;
+    }
+}
+
+/// Check that every named array has the same even length.
+pub(crate) fn check_lengths(arrays: &[(&str, usize)]) -> Result<(), String> {
+    let Some(&(first_name, first_len)) = arrays.first() else {
+        return Ok(());
+    };
+
+    if first_len % 2 != 0 {
+        return Err(format!(
+            "{first_name} has odd length {first_len}; expected x, y pairs"
+        ));
+    }
+
+    for &(name, len) in &arrays[1..] {
+        if len != first_len {
+            return Err(format!(
+                "{name} has length {len} but {first_name} has length {first_len}"
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Scalar integration loop for boids `start..count`.
+#[allow(clippy::too_many_arguments)]
+fn integrate_scalar(
+    positions: &mut [f32],
+    velocities: &mut [f32],
+    accelerations: &[f32],
+    start: usize,
+    dt: f32,
+    min_speed: f32,
+    max_speed: f32,
+    drag: f32,
 ) {
     let count = positions.len() / 2;
     let drag_factor = 1.0 - drag;
     let min_speed_sq = min_speed * min_speed;
     let max_speed_sq = max_speed * max_speed;
 
-    for i in 0..count {
+    for i in start..count {
         let idx = i * 2;
 
         // Get current values
@@ -75,10 +519,16 @@ pub fn integrate_all(
             let scale = max_speed / speed_sq.sqrt();
             new_vx *= scale;
             new_vy *= scale;
-        } else if speed_sq < min_speed_sq && speed_sq > 0.0001 {
-            let scale = min_speed / speed_sq.sqrt();
-            new_vx *= scale;
-            new_vy *= scale;
+        } else if speed_sq < min_speed_sq {
+            if speed_sq > SPEED_EPSILON {
+                let scale = min_speed / speed_sq.sqrt();
+                new_vx *= scale;
+                new_vy *= scale;
+            } else {
+                let (hx, hy) = fallback_heading(i);
+                new_vx = hx * min_speed;
+                new_vy = hy * min_speed;
+            }
         }
 
         // Store velocity
@@ -102,6 +552,149 @@ pub fn integrate_all(
     }
 }
 
+/// SIMD integration loop, two boids per `v128`.
+///
+/// Only boids fully covered by all three arrays are processed here; the
+/// returned index is the first boid left for the scalar loop.
+#[cfg(target_feature = "simd128")]
+fn integrate_simd(
+    positions: &mut [f32],
+    velocities: &mut [f32],
+    accelerations: &[f32],
+    dt: f32,
+    min_speed: f32,
+    max_speed: f32,
+    drag: f32,
+) -> usize {
+    let args = (dt, min_speed, max_speed, drag);
+
+    // Chunks advance 16 bytes at a time, so aligned bases stay aligned
+    if is_simd_aligned(positions) && is_simd_aligned(velocities) && is_simd_aligned(accelerations) {
+        integrate_simd_chunks::<true>(positions, velocities, accelerations, args)
+    } else {
+        integrate_simd_chunks::<false>(positions, velocities, accelerations, args)
+    }
+}
+
+/// [`integrate_simd`] body, using aligned loads and stores when `ALIGNED`.
+#[cfg(target_feature = "simd128")]
+fn integrate_simd_chunks<const ALIGNED: bool>(
+    positions: &mut [f32],
+    velocities: &mut [f32],
+    accelerations: &[f32],
+    (dt, min_speed, max_speed, drag): (f32, f32, f32, f32),
+) -> usize {
+    use core::arch::wasm32::*;
+
+    let len = positions
+        .len()
+        .min(velocities.len())
+        .min(accelerations.len());
+    let chunks = len / 4;
+
+    let dt_v = f32x4_splat(dt);
+    let drag_v = f32x4_splat(1.0 - drag);
+    let min_speed_v = f32x4_splat(min_speed);
+    let max_speed_v = f32x4_splat(max_speed);
+    let min_sq_v = f32x4_splat(min_speed * min_speed);
+    let max_sq_v = f32x4_splat(max_speed * max_speed);
+    let eps_v = f32x4_splat(SPEED_EPSILON);
+    let one_v = f32x4_splat(1.0);
+
+    for c in 0..chunks {
+        let idx = c * 4;
+
+        // SAFETY: `idx + 4 <= len`, which is bounded by every slice length,
+        // and `ALIGNED` is only set when every base pointer is 16-byte
+        // aligned.
+        unsafe {
+            let pos_ptr = positions.as_mut_ptr().add(idx) as *mut v128;
+            let vel_ptr = velocities.as_mut_ptr().add(idx) as *mut v128;
+            let acc_ptr = accelerations.as_ptr().add(idx) as *const v128;
+
+            let p = load_v128::<ALIGNED>(pos_ptr);
+            let v = load_v128::<ALIGNED>(vel_ptr);
+            let a = load_v128::<ALIGNED>(acc_ptr);
+
+            // v = (v + a * dt) * (1 - drag)
+            let v = f32x4_mul(f32x4_add(v, f32x4_mul(a, dt_v)), drag_v);
+
+            // Per-boid speed squared, broadcast to both lanes of each pair
+            let sq = f32x4_mul(v, v);
+            let speed_sq = f32x4_add(sq, i32x4_shuffle::<1, 0, 3, 2>(sq, sq));
+            let speed = f32x4_sqrt(speed_sq);
+
+            // Branchless clamp: pick the scale per lane from the masks
+            let too_fast = f32x4_gt(speed_sq, max_sq_v);
+            let too_slow = v128_and(f32x4_lt(speed_sq, min_sq_v), f32x4_gt(speed_sq, eps_v));
+            let scale_max = f32x4_div(max_speed_v, speed);
+            let scale_min = f32x4_div(min_speed_v, speed);
+            let scale = v128_bitselect(
+                scale_max,
+                v128_bitselect(scale_min, one_v, too_slow),
+                too_fast,
+            );
+            let mut v = f32x4_mul(v, scale);
+
+            // Stalled boids have no heading to scale; this is rare enough
+            // that building the fallback vector behind a branch is cheaper.
+            let stalled = v128_and(f32x4_le(speed_sq, eps_v), f32x4_lt(speed_sq, min_sq_v));
+            if v128_any_true(stalled) {
+                let (ax, ay) = fallback_heading(c * 2);
+                let (bx, by) = fallback_heading(c * 2 + 1);
+                let fallback = f32x4_mul(f32x4(ax, ay, bx, by), min_speed_v);
+                v = v128_bitselect(fallback, v, stalled);
+            }
+
+            store_v128::<ALIGNED>(vel_ptr, v);
+            store_v128::<ALIGNED>(pos_ptr, f32x4_add(p, f32x4_mul(v, dt_v)));
+        }
+    }
+
+    chunks * 2
+}
+
+/// Whether `values` starts on a 16-byte boundary, allowing aligned `v128`
+/// access.
+#[cfg(target_feature = "simd128")]
+#[inline]
+fn is_simd_aligned(values: &[f32]) -> bool {
+    (values.as_ptr() as usize).is_multiple_of(16)
+}
+
+/// Load a `v128`, as an aligned access when `ALIGNED`.
+///
+/// # Safety
+/// `ptr` must be valid for a 16-byte read, and 16-byte aligned if `ALIGNED`.
+#[cfg(target_feature = "simd128")]
+#[inline(always)]
+unsafe fn load_v128<const ALIGNED: bool>(
+    ptr: *const core::arch::wasm32::v128,
+) -> core::arch::wasm32::v128 {
+    if ALIGNED {
+        ptr.read()
+    } else {
+        core::arch::wasm32::v128_load(ptr)
+    }
+}
+
+/// Store a `v128`, as an aligned access when `ALIGNED`.
+///
+/// # Safety
+/// `ptr` must be valid for a 16-byte write, and 16-byte aligned if `ALIGNED`.
+#[cfg(target_feature = "simd128")]
+#[inline(always)]
+unsafe fn store_v128<const ALIGNED: bool>(
+    ptr: *mut core::arch::wasm32::v128,
+    value: core::arch::wasm32::v128,
+) {
+    if ALIGNED {
+        ptr.write(value)
+    } else {
+        core::arch::wasm32::v128_store(ptr, value)
+    }
+}
+
 /// Batch apply drag to velocities.
 ///
 /// # Arguments
@@ -115,42 +708,180 @@ pub fn apply_drag_all(velocities: &mut [f32], drag: f32) {
     }
 }
 
+/// Batch apply a per-boid drag to velocities.
+///
+/// Each drag is clamped to [0, 1]; boids without a drag entry are left
+/// unchanged.
+///
+/// # Arguments
+/// * `velocities` - Mutable array of velocities (x, y pairs)
+/// * `drags` - Drag coefficient per boid
+#[wasm_bindgen]
+pub fn apply_drag_per_boid(velocities: &mut [f32], drags: &[f32]) {
+    for (v, &drag) in velocities.chunks_exact_mut(2).zip(drags) {
+        let factor = 1.0 - drag.clamp(0.0, 1.0);
+        v[0] *= factor;
+        v[1] *= factor;
+    }
+}
+
 /// Batch clamp speeds to min/max range.
 ///
+/// Boids with (near) zero velocity are given `min_speed` along a stable
+/// per-index heading so they never stay frozen.
+///
 /// # Arguments
 /// * `velocities` - Mutable array of velocities (x, y pairs)
 /// * `min_speed` - Minimum speed
 /// * `max_speed` - Maximum speed
 #[wasm_bindgen]
 pub fn clamp_speeds_all(velocities: &mut [f32], min_speed: f32, max_speed: f32) {
+    clamp_speeds_all_counted(velocities, min_speed, max_speed);
+}
+
+/// Same as [`clamp_speeds_all`], returning how many boids were slowed down
+/// to `max_speed`.
+///
+/// A consistently high count usually means the force weights are too strong.
+///
+/// # Arguments
+/// * `velocities` - Mutable array of velocities (x, y pairs)
+/// * `min_speed` - Minimum speed
+/// * `max_speed` - Maximum speed
+#[wasm_bindgen]
+pub fn clamp_speeds_all_counted(velocities: &mut [f32], min_speed: f32, max_speed: f32) -> u32 {
     let count = velocities.len() / 2;
-    let min_sq = min_speed * min_speed;
     let max_sq = max_speed * max_speed;
+    let mut clamped = 0;
 
     for i in 0..count {
-        let idx = i * 2;
-        let vx = velocities.get(idx).copied().unwrap_or(0.0);
-        let vy = velocities.get(idx + 1).copied().unwrap_or(0.0);
+        let (vx, vy) = read_pair(velocities, i);
+        if vx * vx + vy * vy > max_sq {
+            clamped += 1;
+        }
+        let (vx, vy) = clamp_speed(i, vx, vy, min_speed, max_speed);
+        velocities[i * 2] = vx;
+        velocities[i * 2 + 1] = vy;
+    }
+
+    clamped
+}
+
+/// Batch clamp speeds with per-boid limits.
+///
+/// When a limit array is shorter than the flock, its last value applies to
+/// the remaining boids; an empty array leaves that side unclamped.
+///
+/// # Arguments
+/// * `velocities` - Mutable array of velocities (x, y pairs)
+/// * `min_speeds` - Minimum speed per boid
+/// * `max_speeds` - Maximum speed per boid
+#[wasm_bindgen]
+pub fn clamp_speeds_per_boid(velocities: &mut [f32], min_speeds: &[f32], max_speeds: &[f32]) {
+    let count = velocities.len() / 2;
+
+    for i in 0..count {
+        let min_speed = min_speeds
+            .get(i)
+            .or(min_speeds.last())
+            .copied()
+            .unwrap_or(0.0);
+        let max_speed = max_speeds
+            .get(i)
+            .or(max_speeds.last())
+            .copied()
+            .unwrap_or(f32::INFINITY);
+
+        let (vx, vy) = read_pair(velocities, i);
+        let (vx, vy) = clamp_speed(i, vx, vy, min_speed, max_speed);
+        velocities[i * 2] = vx;
+        velocities[i * 2 + 1] = vy;
+    }
+}
+
+/// Batch clamp speeds with a dead band around each limit.
+///
+/// Boids only slow to `max_speed` once they exceed `max_speed + band`, and
+/// only speed up to `min_speed` once they drop below `min_speed - band`, so
+/// boids hovering near a limit are not clamped on and off every frame.
+///
+/// # Arguments
+/// * `velocities` - Mutable array of velocities (x, y pairs)
+/// * `min_speed` - Minimum speed
+/// * `max_speed` - Maximum speed
+/// * `band` - Tolerance either side of the limits
+#[wasm_bindgen]
+pub fn clamp_speeds_hysteresis(velocities: &mut [f32], min_speed: f32, max_speed: f32, band: f32) {
+    let band = band.max(0.0);
+    let low = (min_speed - band).max(0.0);
+    let high = max_speed + band;
+
+    for i in 0..velocities.len() / 2 {
+        let (vx, vy) = read_pair(velocities, i);
+        let speed_sq = vx * vx + vy * vy;
+        if speed_sq >= low * low && speed_sq <= high * high {
+            continue;
+        }
+
+        let (vx, vy) = clamp_speed(i, vx, vy, min_speed, max_speed);
+        velocities[i * 2] = vx;
+        velocities[i * 2 + 1] = vy;
+    }
+}
+
+/// Batch smoothly saturate speeds toward `max_speed`.
+///
+/// Each speed `s` becomes `max_speed * tanh(softness * s / max_speed)`,
+/// keeping the heading, so speeds approach `max_speed` without a hard
+/// wall in the speed distribution. With `softness` 1, low speeds are
+/// nearly unchanged; larger values saturate sooner. Velocities are left
+/// untouched unless both `max_speed` and `softness` are positive.
+///
+/// # Arguments
+/// * `velocities` - Mutable array of velocities (x, y pairs)
+/// * `max_speed` - Asymptotic speed limit
+/// * `softness` - Steepness of the saturation curve
+#[wasm_bindgen]
+pub fn soft_limit_speeds(velocities: &mut [f32], max_speed: f32, softness: f32) {
+    if max_speed.is_nan() || max_speed <= 0.0 || softness.is_nan() || softness <= 0.0 {
+        return;
+    }
+
+    for v in velocities.chunks_exact_mut(2) {
+        let speed = (v[0] * v[0] + v[1] * v[1]).sqrt();
+        if speed > 0.0 {
+            let ratio = speed / max_speed;
+            let scale = (ratio * softness).tanh() / ratio;
+            v[0] *= scale;
+            v[1] *= scale;
+        }
+    }
+}
 
-        let speed_sq = vx * vx + vy * vy;
+/// Clamp one velocity into [min_speed, max_speed], restarting stalled boids.
+#[inline]
+pub(crate) fn clamp_speed(
+    i: usize,
+    vx: f32,
+    vy: f32,
+    min_speed: f32,
+    max_speed: f32,
+) -> (f32, f32) {
+    let speed_sq = vx * vx + vy * vy;
 
-        if speed_sq > max_sq {
-            let scale = max_speed / speed_sq.sqrt();
-            if let Some(v) = velocities.get_mut(idx) {
-                *v = vx * scale;
-            }
-            if let Some(v) = velocities.get_mut(idx + 1) {
-                *v = vy * scale;
-            }
-        } else if speed_sq < min_sq && speed_sq > 0.0001 {
+    if speed_sq > max_speed * max_speed {
+        let scale = max_speed / speed_sq.sqrt();
+        (vx * scale, vy * scale)
+    } else if speed_sq < min_speed * min_speed {
+        if speed_sq > SPEED_EPSILON {
             let scale = min_speed / speed_sq.sqrt();
-            if let Some(v) = velocities.get_mut(idx) {
-                *v = vx * scale;
-            }
-            if let Some(v) = velocities.get_mut(idx + 1) {
-                *v = vy * scale;
-            }
+            (vx * scale, vy * scale)
+        } else {
+            let (hx, hy) = fallback_heading(i);
+            (hx * min_speed, hy * min_speed)
         }
+    } else {
+        (vx, vy)
     }
 }
 
@@ -193,6 +924,95 @@ pub fn compute_distances_batch(positions: &[f32], targets: &[f32], out: &mut [f3
     }
 }
 
+/// Batch compute squared distances to the nearest target on a toroidal world.
+///
+/// Like [`compute_distances_batch`], but each axis uses the shorter of the
+/// direct and the wrapped-around distance, matching `wrap_positions_all`.
+///
+/// # Arguments
+/// * `positions` - Array of positions (x, y pairs)
+/// * `targets` - Array of target positions (x, y pairs)
+/// * `out` - Output array for squared distances (one per position)
+/// * `width` - World width
+/// * `height` - World height
+#[wasm_bindgen]
+pub fn compute_distances_batch_wrapped(
+    positions: &[f32],
+    targets: &[f32],
+    out: &mut [f32],
+    width: f32,
+    height: f32,
+) {
+    let count = positions.len() / 2;
+    let target_count = targets.len() / 2;
+
+    for i in 0..count {
+        let (px, py) = read_pair(positions, i);
+        let mut min_dist_sq = f32::MAX;
+
+        for j in 0..target_count {
+            let (tx, ty) = read_pair(targets, j);
+            let dx = wrap_delta(px - tx, width);
+            let dy = wrap_delta(py - ty, height);
+            let dist_sq = dx * dx + dy * dy;
+
+            if dist_sq < min_dist_sq {
+                min_dist_sq = dist_sq;
+            }
+        }
+
+        if let Some(o) = out.get_mut(i) {
+            *o = min_dist_sq;
+        }
+    }
+}
+
+/// Batch find the nearest target for every position.
+///
+/// Writes the squared distance and the index of the nearest target. With no
+/// targets the distance is `f32::MAX` and the index is `u32::MAX`.
+///
+/// # Arguments
+/// * `positions` - Array of positions (x, y pairs)
+/// * `targets` - Array of target positions (x, y pairs)
+/// * `out_dist` - Output array for squared distances (one per position)
+/// * `out_idx` - Output array for nearest target indices (one per position)
+#[wasm_bindgen]
+pub fn compute_nearest_targets(
+    positions: &[f32],
+    targets: &[f32],
+    out_dist: &mut [f32],
+    out_idx: &mut [u32],
+) {
+    let count = positions.len() / 2;
+    let target_count = targets.len() / 2;
+
+    for i in 0..count {
+        let (px, py) = read_pair(positions, i);
+        let mut min_dist_sq = f32::MAX;
+        let mut nearest = u32::MAX;
+
+        for j in 0..target_count {
+            let (tx, ty) = read_pair(targets, j);
+            let dx = px - tx;
+            let dy = py - ty;
+            let dist_sq = dx * dx + dy * dy;
+
+            if dist_sq < min_dist_sq {
+                min_dist_sq = dist_sq;
+                nearest = j as u32;
+            }
+        }
+
+        if let Some(o) = out_dist.get_mut(i) {
+            *o = min_dist_sq;
+        }
+        if let Some(o) = out_idx.get_mut(i) {
+            *o = nearest;
+        }
+    }
+}
+
 /// Batch wrap positions to world bounds (toroidal wrapping).
 ///
 /// # Arguments
@@ -226,17 +1046,22 @@ pub fn wrap_positions_all(positions: &mut [f32], width: f32, height: f32) {
 
 /// Batch bounce positions off world bounds.
 ///
+/// A boid that overshoots a wall is reflected back inside by the overshoot
+/// distance, so slow boids do not stick to the wall and re-bounce every frame.
+///
 /// # Arguments
 /// * `positions` - Mutable array of positions (x, y pairs)
 /// * `velocities` - Mutable array of velocities (x, y pairs)
 /// * `width` - World width
 /// * `height` - World height
+/// * `restitution` - Fraction of normal velocity kept on impact (1 = elastic)
 #[wasm_bindgen]
 pub fn bounce_positions_all(
     positions: &mut [f32],
     velocities: &mut [f32],
     width: f32,
     height: f32,
+    restitution: f32,
 ) {
     let count = positions.len() / 2;
 
@@ -245,28 +1070,39 @@ pub fn bounce_positions_all(
 
         // X bounds
         if let (Some(px), Some(vx)) = (positions.get_mut(idx), velocities.get_mut(idx)) {
-            if *px < 0.0 {
-                *px = 0.0;
-                *vx = vx.abs();
-            } else if *px >= width {
-                *px = width - 0.001;
-                *vx = -vx.abs();
-            }
+            bounce_axis(px, vx, width, restitution);
         }
 
         // Y bounds
         if let (Some(py), Some(vy)) = (positions.get_mut(idx + 1), velocities.get_mut(idx + 1)) {
-            if *py < 0.0 {
-                *py = 0.0;
-                *vy = vy.abs();
-            } else if *py >= height {
-                *py = height - 0.001;
-                *vy = -vy.abs();
-            }
+            bounce_axis(py, vy, height, restitution);
         }
     }
 }
 
+/// Reflect one coordinate into `[0, size)`, flipping its velocity inward
+/// and scaling it by `restitution`.
+#[inline]
+pub(crate) fn bounce_axis(p: &mut f32, v: &mut f32, size: f32, restitution: f32) {
+    // No room to bounce in, e.g. an unsized canvas on the first frame
+    if size.is_nan() || size <= 0.0 {
+        return;
+    }
+
+    if *p < 0.0 {
+        *p = -*p;
+        *v = v.abs() * restitution;
+    } else if *p >= size {
+        *p = 2.0 * size - *p;
+        *v = -v.abs() * restitution;
+    } else {
+        return;
+    }
+
+    // Overshoot larger than the world itself
+    *p = p.min(size - 0.001).max(0.0);
+}
+
 /// Batch reset accelerations to zero.
 ///
 /// # Arguments
@@ -299,6 +1135,54 @@ pub fn add_force_all(accelerations: &mut [f32], force_x: f32, force_y: f32) {
     }
 }
 
+/// Batch overwrite accelerations with a single force.
+///
+/// Equivalent to [`reset_accelerations_all`] followed by [`add_force_all`],
+/// in one pass.
+///
+/// # Arguments
+/// * `accelerations` - Mutable array of accelerations (x, y pairs)
+/// * `force_x` - X component of force
+/// * `force_y` - Y component of force
+#[wasm_bindgen]
+pub fn set_force_all(accelerations: &mut [f32], force_x: f32, force_y: f32) {
+    for a in accelerations.chunks_exact_mut(2) {
+        a[0] = force_x;
+        a[1] = force_y;
+    }
+}
+
+/// Batch low-pass filter accelerations against the previous frame's.
+///
+/// Each component becomes `prev + (a - prev) * smoothing`: 1.0 keeps the raw
+/// acceleration, 0.0 holds the previous one. Components without a previous
+/// value are left unchanged.
+///
+/// # Arguments
+/// * `accelerations` - Mutable array of accelerations (x, y pairs)
+/// * `prev_accelerations` - Previous frame's accelerations (x, y pairs)
+/// * `smoothing` - Blend factor toward the new acceleration
+#[wasm_bindgen]
+pub fn smooth_accelerations(accelerations: &mut [f32], prev_accelerations: &[f32], smoothing: f32) {
+    for (a, &prev) in accelerations.iter_mut().zip(prev_accelerations) {
+        *a = prev + (*a - prev) * smoothing;
+    }
+}
+
+/// Batch clamp acceleration magnitudes to `max_force`, preserving direction.
+///
+/// # Arguments
+/// * `accelerations` - Mutable array of accelerations (x, y pairs)
+/// * `max_force` - Maximum force magnitude
+#[wasm_bindgen]
+pub fn clamp_forces_all(accelerations: &mut [f32], max_force: f32) {
+    for a in accelerations.chunks_exact_mut(2) {
+        let (ax, ay) = clamp_magnitude(a[0], a[1], max_force);
+        a[0] = ax;
+        a[1] = ay;
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -323,6 +1207,56 @@ mod tests {
         assert!((positions[2] - 10.0).abs() < 0.001);
     }
 
+    #[cfg(target_feature = "simd128")]
+    #[test]
+    fn test_integrate_simd_matches_scalar() {
+        // Odd boid count exercises the scalar tail; speeds cover the
+        // too-fast, too-slow, near-zero and in-range branches.
+        let positions = vec![0.0, 0.0, 10.0, 10.0, 5.0, -3.0, 1.0, 2.0, 7.5, 7.5];
+        let velocities = vec![20.0, 5.0, 0.2, 0.1, 0.001, 0.0, 2.0, -1.5, -9.0, 3.0];
+        let accelerations = vec![1.0, -1.0, 0.5, 0.5, 0.0, 0.0, -0.3, 0.2, 4.0, 4.0];
+
+        let mut simd_pos = positions.clone();
+        let mut simd_vel = velocities.clone();
+        let start = integrate_simd(
+            &mut simd_pos,
+            &mut simd_vel,
+            &accelerations,
+            0.016,
+            1.0,
+            8.0,
+            0.01,
+        );
+        integrate_scalar(
+            &mut simd_pos,
+            &mut simd_vel,
+            &accelerations,
+            start,
+            0.016,
+            1.0,
+            8.0,
+            0.01,
+        );
+
+        let mut scalar_pos = positions;
+        let mut scalar_vel = velocities;
+        integrate_scalar(
+            &mut scalar_pos,
+            &mut scalar_vel,
+            &accelerations,
+            0,
+            0.016,
+            1.0,
+            8.0,
+            0.01,
+        );
+
+        for i in 0..simd_pos.len() {
+            assert!((simd_pos[i] - scalar_pos[i]).abs() < 1e-5);
+            assert!((simd_vel[i] - scalar_vel[i]).abs() < 1e-5);
+        }
+    }
+
     #[test]
     fn test_clamp_speeds() {
         let mut velocities = vec![10.0, 0.0, 0.1, 0.0];
@@ -336,6 +1270,186 @@ mod tests {
         assert!((speed2 - 1.0).abs() < 0.001);
     }
 
+    #[test]
+    fn test_integrate_aligned_matches_misaligned() {
+        #[repr(align(16))]
+        struct Aligned([f32; 20]);
+
+        let init = |k: f32| -> Aligned {
+            Aligned(core::array::from_fn(|i| {
+                ((i as f32 + k) * 0.37).sin() * 6.0
+            }))
+        };
+        let (mut pos, mut vel, acc) = (init(1.0), init(2.0), init(3.0));
+        let (mut pos_off, mut vel_off, mut acc_off) = (init(0.0), init(0.0), init(0.0));
+
+        // Same data shifted by one float, so every base is 4 bytes off a
+        // 16-byte boundary
+        pos_off.0[1..17].copy_from_slice(&pos.0[..16]);
+        vel_off.0[1..17].copy_from_slice(&vel.0[..16]);
+        acc_off.0[1..17].copy_from_slice(&acc.0[..16]);
+
+        integrate_all(
+            &mut pos.0[..16],
+            &mut vel.0[..16],
+            &acc.0[..16],
+            0.1,
+            1.0,
+            5.0,
+            0.02,
+        );
+        integrate_all(
+            &mut pos_off.0[1..17],
+            &mut vel_off.0[1..17],
+            &acc_off.0[1..17],
+            0.1,
+            1.0,
+            5.0,
+            0.02,
+        );
+
+        assert_eq!(pos.0[..16], pos_off.0[1..17]);
+        assert_eq!(vel.0[..16], vel_off.0[1..17]);
+    }
+
+    #[test]
+    fn test_try_integrate_rejects_mismatched_lengths() {
+        let err = check_lengths(&[("positions", 4), ("velocities", 2), ("accelerations", 4)])
+            .unwrap_err();
+        assert_eq!(err, "velocities has length 2 but positions has length 4");
+
+        let err = check_lengths(&[("positions", 3), ("velocities", 3)]).unwrap_err();
+        assert!(err.contains("odd length 3"));
+
+        let mut positions = vec![0.0, 0.0];
+        let mut velocities = vec![1.0, 0.0];
+        assert!(try_integrate_all(
+            &mut positions,
+            &mut velocities,
+            &[0.0; 2],
+            1.0,
+            0.0,
+            5.0,
+            0.0
+        )
+        .is_ok());
+        assert_eq!(positions, vec![1.0, 0.0]);
+    }
+
+    #[test]
+    fn test_clamp_speeds_counted() {
+        let mut velocities = vec![10.0, 0.0, 0.0, 2.0, -6.0, 8.0];
+
+        let clamped = clamp_speeds_all_counted(&mut velocities, 1.0, 5.0);
+
+        assert_eq!(clamped, 2);
+        assert!((velocities[4] + 3.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_distances_wrapped() {
+        let positions = vec![1.0, 50.0];
+        let targets = vec![99.0, 50.0];
+        let mut out = vec![0.0];
+
+        compute_distances_batch_wrapped(&positions, &targets, &mut out, 100.0, 100.0);
+        assert!((out[0] - 4.0).abs() < 0.001);
+
+        compute_distances_batch(&positions, &targets, &mut out);
+        assert!((out[0] - 9604.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_nearest_targets() {
+        let positions = vec![9.0, 1.0];
+        let targets = vec![0.0, 0.0, 10.0, 0.0, 20.0, 0.0];
+        let mut dist = vec![0.0];
+        let mut idx = vec![0];
+
+        compute_nearest_targets(&positions, &targets, &mut dist, &mut idx);
+        assert_eq!(idx[0], 1);
+        assert!((dist[0] - 2.0).abs() < 0.001);
+
+        compute_nearest_targets(&positions, &[], &mut dist, &mut idx);
+        assert_eq!(idx[0], u32::MAX);
+        assert_eq!(dist[0], f32::MAX);
+    }
+
+    #[test]
+    fn test_min_speed_from_zero_velocity() {
+        let mut velocities = vec![0.0, 0.0, 0.0, 0.0];
+        clamp_speeds_all(&mut velocities, 2.0, 5.0);
+
+        for v in velocities.chunks(2) {
+            assert!(((v[0] * v[0] + v[1] * v[1]).sqrt() - 2.0).abs() < 0.001);
+        }
+        // Reproducible and distinct per boid
+        assert_eq!(velocities[0..2], [2.0, 0.0]);
+        assert!(velocities[2..4] != velocities[0..2]);
+
+        let mut positions = vec![0.0; 6];
+        let mut velocities = vec![0.0; 6];
+        integrate_all(
+            &mut positions,
+            &mut velocities,
+            &[0.0; 6],
+            1.0,
+            2.0,
+            5.0,
+            0.0,
+        );
+
+        for v in velocities.chunks(2) {
+            assert!(((v[0] * v[0] + v[1] * v[1]).sqrt() - 2.0).abs() < 0.001);
+        }
+    }
+
+    #[test]
+    fn test_clamp_speeds_per_boid() {
+        let mut velocities = vec![10.0, 0.0, 0.0, 10.0, 0.0, 10.0];
+
+        clamp_speeds_per_boid(&mut velocities, &[0.0], &[3.0, 6.0]);
+
+        assert!((velocities[0] - 3.0).abs() < 0.001);
+        assert!((velocities[3] - 6.0).abs() < 0.001);
+        // Third boid reuses the last max
+        assert!((velocities[5] - 6.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_bounce_reflects_overshoot() {
+        let mut positions = vec![105.0, 50.0, 50.0, -3.0];
+        let mut velocities = vec![2.0, 0.0, 0.0, -1.0];
+
+        bounce_positions_all(&mut positions, &mut velocities, 100.0, 100.0, 1.0);
+
+        assert!((positions[0] - 95.0).abs() < 0.001);
+        assert_eq!(velocities[0], -2.0);
+        assert!((positions[3] - 3.0).abs() < 0.001);
+        assert_eq!(velocities[3], 1.0);
+    }
+
+    #[test]
+    fn test_bounce_restitution() {
+        let mut positions = vec![101.0, 50.0];
+        let mut velocities = vec![4.0, 1.0];
+
+        bounce_positions_all(&mut positions, &mut velocities, 100.0, 100.0, 0.5);
+
+        assert_eq!(velocities[0], -2.0);
+        // Tangential velocity is untouched
+        assert_eq!(velocities[1], 1.0);
+    }
+
+    #[test]
+    fn test_clamp_forces() {
+        let mut accelerations = vec![10.0, 0.0, 1.0, 0.0];
+
+        clamp_forces_all(&mut accelerations, 3.0);
+
+        assert_eq!(accelerations, vec![3.0, 0.0, 1.0, 0.0]);
+    }
+
     #[test]
     fn test_wrap_positions() {
         let mut positions = vec![-1.0, 101.0];
@@ -345,6 +1459,199 @@ mod tests {
         assert!((positions[0] - 99.0).abs() < 0.001);
         assert!((positions[1] - 1.0).abs() < 0.001);
     }
-}
 
+    #[test]
+    fn test_soft_limit_speeds_non_positive_softness() {
+        let mut velocities = vec![3.0, 4.0, -6.0, 8.0];
+
+        // Zero or negative softness would zero or reverse velocities
+        soft_limit_speeds(&mut velocities, 5.0, 0.0);
+        soft_limit_speeds(&mut velocities, 5.0, -1.0);
+        soft_limit_speeds(&mut velocities, 5.0, f32::NAN);
+        assert_eq!(velocities, vec![3.0, 4.0, -6.0, 8.0]);
+    }
+
+    #[test]
+    fn test_bounce_zero_size_world() {
+        let mut positions = vec![5.0, -2.0, 0.0, 0.0];
+        let mut velocities = vec![1.0, -1.0, 1.0, 1.0];
+
+        bounce_positions_all(&mut positions, &mut velocities, 0.0, 0.0, 1.0);
+        assert_eq!(positions, vec![5.0, -2.0, 0.0, 0.0]);
+        assert_eq!(velocities, vec![1.0, -1.0, 1.0, 1.0]);
+
+        // Worlds narrower than the inset stay in range without panicking
+        bounce_positions_all(&mut positions, &mut velocities, 0.0005, f32::NAN, 1.0);
+        assert!((0.0..=0.0005).contains(&positions[0]));
+    }
+
+    #[test]
+    fn test_set_force_overwrites() {
+        let mut accel = vec![3.0, -7.0, 0.5, 12.0];
+
+        set_force_all(&mut accel, 0.0, 0.2);
+
+        assert_eq!(accel, vec![0.0, 0.2, 0.0, 0.2]);
+    }
+
+    #[test]
+    fn test_smooth_accelerations_step() {
+        let mut accel = vec![4.0, -2.0];
+
+        smooth_accelerations(&mut accel, &[0.0, 0.0], 0.5);
+
+        assert_eq!(accel, vec![2.0, -1.0]);
+    }
+
+    #[test]
+    fn test_integrate_masked_skips_inactive() {
+        let mut positions = vec![0.0, 0.0, 5.0, 5.0, 1.0, 1.0];
+        let mut velocities = vec![1.0, 0.0, 1.0, 0.0, 0.0, 1.0];
+
+        integrate_all_masked(
+            &mut positions,
+            &mut velocities,
+            &[0.5; 6],
+            &[1, 0],
+            &mut [],
+            1.0,
+            0.0,
+            10.0,
+            0.0,
+        );
+
+        assert_eq!(positions, vec![1.5, 0.5, 5.0, 5.0, 1.5, 2.5]);
+        assert_eq!(&velocities[2..4], &[1.0, 0.0]);
+    }
+
+    #[test]
+    fn test_turn_limited_reversal() {
+        let mut positions = vec![0.0, 0.0];
+        let mut velocities = vec![1.0, 0.0];
+
+        integrate_all_turn_limited(
+            &mut positions,
+            &mut velocities,
+            &[-3.0, 0.0],
+            1.0,
+            0.0,
+            10.0,
+            0.0,
+            0.5,
+        );
+
+        // Speed follows the force (2), heading turns only 0.5 rad
+        let heading = velocities[1].atan2(velocities[0]);
+        let speed = (velocities[0] * velocities[0] + velocities[1] * velocities[1]).sqrt();
+        assert!((heading - 0.5).abs() < 1e-5);
+        assert!((speed - 2.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_drag_per_boid() {
+        let mut velocities = vec![2.0, 2.0, 2.0, -4.0, 1.0, 1.0];
+
+        apply_drag_per_boid(&mut velocities, &[0.0, 0.5, 3.0]);
+
+        assert_eq!(velocities, vec![2.0, 2.0, 1.0, -2.0, 0.0, 0.0]);
+    }
 
+    #[test]
+    fn test_sleeping_boids() {
+        let mut velocities = vec![0.01, 0.0, 3.0, 0.0, 0.0, 0.02];
+        let mut sleeping = vec![9; 3];
+
+        mark_sleeping(&velocities, &mut sleeping, 0.1);
+        assert_eq!(sleeping, vec![1, 0, 1]);
+
+        // The third boid is pushed and wakes
+        let mut positions = vec![0.0; 6];
+        let accelerations = vec![0.0, 0.0, 0.0, 0.0, 0.5, 0.0];
+        integrate_all_masked(
+            &mut positions,
+            &mut velocities,
+            &accelerations,
+            &[],
+            &mut sleeping,
+            1.0,
+            0.0,
+            10.0,
+            0.0,
+        );
+
+        assert_eq!(&positions[..2], &[0.0, 0.0]);
+        assert_eq!(positions[2], 3.0);
+        assert_eq!(positions[4], 0.5);
+        assert_eq!(sleeping, vec![1, 0, 0]);
+    }
+
+    #[test]
+    fn test_feature_flags_match_build() {
+        let flags = feature_flags();
+
+        assert_eq!(flags & FEATURE_SIMD != 0, cfg!(target_feature = "simd128"));
+        assert_eq!(flags & FEATURE_WEE_ALLOC != 0, cfg!(feature = "wee_alloc"));
+        assert_eq!(
+            flags & FEATURE_PANIC_HOOK != 0,
+            cfg!(feature = "console_error_panic_hook")
+        );
+        assert_eq!(version(), env!("CARGO_PKG_VERSION"));
+        assert_eq!(epsilon(), SPEED_EPSILON);
+    }
+
+    #[test]
+    fn test_clamp_speeds_hysteresis() {
+        let mut velocities = vec![10.4, 0.0, 0.0, 10.6, 1.8, 0.0, 1.0, 0.0];
+
+        clamp_speeds_hysteresis(&mut velocities, 2.0, 10.0, 0.5);
+
+        // Inside the band: untouched
+        assert_eq!(&velocities[..2], &[10.4, 0.0]);
+        assert_eq!(&velocities[4..6], &[1.8, 0.0]);
+        // Past the band: clamped to the limit itself
+        assert!((velocities[3] - 10.0).abs() < 1e-5);
+        assert!((velocities[6] - 2.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_simd_toggle_matches() {
+        let positions: Vec<f32> = (0..42).map(|i| i as f32 * 1.5 - 20.0).collect();
+        let velocities: Vec<f32> = (0..42).map(|i| ((i * 7 % 11) as f32 - 5.0) * 0.9).collect();
+        let accelerations: Vec<f32> = (0..42).map(|i| (i as f32 * 0.3).cos()).collect();
+
+        let run = |enabled: bool| {
+            set_simd_enabled(enabled);
+            let mut pos = positions.clone();
+            let mut vel = velocities.clone();
+            integrate_all(&mut pos, &mut vel, &accelerations, 0.1, 1.0, 5.0, 0.02);
+            let mut speeds = vec![0.0; 21];
+            compute_speeds(&vel, &mut speeds);
+            let mut bounds = [0.0; 4];
+            flock_bounds(&pos, &mut bounds);
+            (pos, vel, speeds, bounds)
+        };
+
+        let simd = run(true);
+        let scalar = run(false);
+        assert!(!simd_enabled());
+        set_simd_enabled(true);
+
+        assert_eq!(simd, scalar);
+        assert_eq!(simd_enabled(), cfg!(target_feature = "simd128"));
+    }
+
+    #[test]
+    fn test_soft_limit_speeds() {
+        let mut velocities = vec![0.1, 0.0, 0.0, -3.0, 6.0, 8.0, 1000.0, 0.0];
+
+        soft_limit_speeds(&mut velocities, 5.0, 1.0);
+
+        for v in velocities.chunks_exact(2) {
+            assert!((v[0] * v[0] + v[1] * v[1]).sqrt() <= 5.0);
+        }
+        assert!((velocities[0] - 0.1).abs() < 1e-4);
+        assert!(velocities[3] < 0.0);
+        // Heading is kept
+        assert!((velocities[5] / velocities[4] - 8.0 / 6.0).abs() < 1e-5);
+    }
+}
@@ -0,0 +1,332 @@
+//! Circular obstacle avoidance.
+//!
+//! Obstacles are interleaved (x, y) centers with a parallel array of radii.
+
+use wasm_bindgen::prelude::*;
+
+use crate::grid::SpatialGrid;
+use crate::{add_pair, fallback_heading, read_pair};
+
+/// Batch steer boids around circular obstacles.
+///
+/// Each boid looks `look_ahead * velocity` ahead; if that point falls inside
+/// an obstacle, it is pushed sideways away from the most threatening
+/// (nearest) one. A boid already inside an obstacle is pushed straight out.
+///
+/// # Arguments
+/// * `positions` - Array of positions (x, y pairs)
+/// * `velocities` - Array of velocities (x, y pairs)
+/// * `out_accel` - Mutable array of accelerations to add into (x, y pairs)
+/// * `obstacles` - Array of obstacle centers (x, y pairs)
+/// * `radius_per_obstacle` - Radius of each obstacle
+/// * `look_ahead` - How many seconds of travel to look ahead
+/// * `strength` - Avoidance force magnitude
+#[wasm_bindgen]
+pub fn avoid_obstacles_all(
+    positions: &[f32],
+    velocities: &[f32],
+    out_accel: &mut [f32],
+    obstacles: &[f32],
+    radius_per_obstacle: &[f32],
+    look_ahead: f32,
+    strength: f32,
+) {
+    let obstacle_count = (obstacles.len() / 2).min(radius_per_obstacle.len());
+
+    for i in 0..positions.len() / 2 {
+        let (fx, fy) = avoidance_for(
+            positions,
+            velocities,
+            i,
+            obstacles,
+            radius_per_obstacle,
+            0..obstacle_count,
+            look_ahead,
+        );
+        add_pair(out_accel, i, fx * strength, fy * strength);
+    }
+}
+
+/// Grid-accelerated [`avoid_obstacles_all`].
+///
+/// `grid` must have been rebuilt from `obstacles` (not from the boids), so
+/// each boid only checks obstacles in the cells spanning its position and
+/// look-ahead point.
+#[wasm_bindgen]
+#[allow(clippy::too_many_arguments)]
+pub fn avoid_obstacles_grid(
+    positions: &[f32],
+    velocities: &[f32],
+    out_accel: &mut [f32],
+    obstacles: &[f32],
+    radius_per_obstacle: &[f32],
+    grid: &SpatialGrid,
+    look_ahead: f32,
+    strength: f32,
+) {
+    let obstacle_count = (obstacles.len() / 2).min(radius_per_obstacle.len());
+    let max_radius = radius_per_obstacle[..obstacle_count]
+        .iter()
+        .copied()
+        .fold(0.0, f32::max);
+
+    for i in 0..positions.len() / 2 {
+        // Square around the midpoint covering both the boid and its
+        // look-ahead point, padded by the largest obstacle radius
+        let (px, py) = read_pair(positions, i);
+        let (vx, vy) = read_pair(velocities, i);
+        let half_x = vx * look_ahead * 0.5;
+        let half_y = vy * look_ahead * 0.5;
+        let extent = half_x.abs().max(half_y.abs()) + max_radius;
+
+        let candidates = grid
+            .candidates(px + half_x, py + half_y, extent)
+            .filter(|&j| j < obstacle_count);
+        let (fx, fy) = avoidance_for(
+            positions,
+            velocities,
+            i,
+            obstacles,
+            radius_per_obstacle,
+            candidates,
+            look_ahead,
+        );
+        add_pair(out_accel, i, fx * strength, fy * strength);
+    }
+}
+
+/// Unit avoidance direction for boid `i` over the candidate obstacles.
+fn avoidance_for(
+    positions: &[f32],
+    velocities: &[f32],
+    i: usize,
+    obstacles: &[f32],
+    radii: &[f32],
+    candidates: impl Iterator<Item = usize>,
+    look_ahead: f32,
+) -> (f32, f32) {
+    let (px, py) = read_pair(positions, i);
+    let (vx, vy) = read_pair(velocities, i);
+    let ahead_x = px + vx * look_ahead;
+    let ahead_y = py + vy * look_ahead;
+
+    // Most threatening obstacle: the nearest one the boid is inside of or
+    // whose disc contains the look-ahead point
+    let mut threat: Option<(usize, f32, bool)> = None;
+
+    for j in candidates {
+        let (cx, cy) = read_pair(obstacles, j);
+        let r = radii.get(j).copied().unwrap_or(0.0);
+        let r_sq = r * r;
+
+        let dist_sq = (px - cx) * (px - cx) + (py - cy) * (py - cy);
+        let inside = dist_sq < r_sq;
+        let ahead_sq = (ahead_x - cx) * (ahead_x - cx) + (ahead_y - cy) * (ahead_y - cy);
+
+        if (inside || ahead_sq < r_sq) && threat.is_none_or(|(_, d, _)| dist_sq < d) {
+            threat = Some((j, dist_sq, inside));
+        }
+    }
+
+    let Some((j, dist_sq, inside)) = threat else {
+        return (0.0, 0.0);
+    };
+    let (cx, cy) = read_pair(obstacles, j);
+
+    if inside {
+        if dist_sq <= 0.0 {
+            return fallback_heading(i);
+        }
+        let dist = dist_sq.sqrt();
+        return ((px - cx) / dist, (py - cy) / dist);
+    }
+
+    // Component of (ahead - center) perpendicular to the heading
+    let speed = (vx * vx + vy * vy).sqrt();
+    let (hx, hy) = if speed > 0.0 {
+        (vx / speed, vy / speed)
+    } else {
+        (0.0, 0.0)
+    };
+    let away_x = ahead_x - cx;
+    let away_y = ahead_y - cy;
+    let along = away_x * hx + away_y * hy;
+    let lat_x = away_x - along * hx;
+    let lat_y = away_y - along * hy;
+    let lat_len = (lat_x * lat_x + lat_y * lat_y).sqrt();
+
+    if lat_len > 1e-6 {
+        (lat_x / lat_len, lat_y / lat_len)
+    } else {
+        // Dead ahead: pick the left-hand side deterministically
+        (-hy, hx)
+    }
+}
+
+/// Distance along a ray to the first circular obstacle it hits.
+///
+/// Returns `max_dist` when nothing is hit within range or the direction is
+/// zero, and 0 when the origin is already inside an obstacle. A ray that
+/// just grazes a circle counts as a hit.
+///
+/// # Arguments
+/// * `origin_x` - Ray origin X coordinate
+/// * `origin_y` - Ray origin Y coordinate
+/// * `dir_x` - Ray direction X component (need not be normalized)
+/// * `dir_y` - Ray direction Y component
+/// * `max_dist` - Maximum distance to test
+/// * `obstacles` - Array of obstacle centers (x, y pairs)
+/// * `radii` - Radius of each obstacle
+#[wasm_bindgen]
+pub fn raycast_obstacles(
+    origin_x: f32,
+    origin_y: f32,
+    dir_x: f32,
+    dir_y: f32,
+    max_dist: f32,
+    obstacles: &[f32],
+    radii: &[f32],
+) -> f32 {
+    let len = (dir_x * dir_x + dir_y * dir_y).sqrt();
+    if len <= 0.0 {
+        return max_dist;
+    }
+    let dx = dir_x / len;
+    let dy = dir_y / len;
+
+    let mut nearest = max_dist;
+
+    for (j, &r) in radii.iter().enumerate().take(obstacles.len() / 2) {
+        let (cx, cy) = read_pair(obstacles, j);
+        let fx = origin_x - cx;
+        let fy = origin_y - cy;
+
+        // |f + t·d|² = r² with |d| = 1: t² + 2bt + c = 0
+        let b = fx * dx + fy * dy;
+        let c = fx * fx + fy * fy - r * r;
+        let disc = b * b - c;
+        if disc < 0.0 {
+            continue;
+        }
+
+        let t = if c <= 0.0 { 0.0 } else { -b - disc.sqrt() };
+        if t >= 0.0 && t < nearest {
+            nearest = t;
+        }
+    }
+
+    nearest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_avoid_obstacle_ahead() {
+        let positions = vec![0.0, 0.0, 0.0, 10.0];
+        let velocities = vec![1.0, 0.0, 1.0, 0.0];
+        let mut accel = vec![0.0; 4];
+
+        // Dead ahead of boid 0, slightly below boid 1's path
+        let obstacles = vec![5.0, 0.0, 5.0, 9.5];
+        let radii = vec![2.0, 2.0];
+
+        avoid_obstacles_all(
+            &positions,
+            &velocities,
+            &mut accel,
+            &obstacles,
+            &radii,
+            5.0,
+            3.0,
+        );
+
+        assert!(accel[0].abs() < 0.001);
+        assert!((accel[1].abs() - 3.0).abs() < 0.001);
+        assert!(accel[3] > 2.9);
+    }
+
+    #[test]
+    fn test_avoid_obstacle_inside() {
+        let positions = vec![1.0, 0.0];
+        let velocities = vec![0.0, 1.0];
+        let mut accel = vec![0.0; 2];
+
+        avoid_obstacles_all(
+            &positions,
+            &velocities,
+            &mut accel,
+            &[0.0, 0.0],
+            &[2.0],
+            1.0,
+            1.0,
+        );
+
+        assert!((accel[0] - 1.0).abs() < 0.001);
+        assert!(accel[1].abs() < 0.001);
+    }
+
+    #[test]
+    fn test_avoid_obstacles_grid_matches_brute_force() {
+        // One obstacle right ahead of the first boid, many far away
+        let mut obstacles = vec![20.0, 10.0];
+        let mut radii = vec![4.0];
+        for k in 0..100 {
+            obstacles.extend_from_slice(&[
+                200.0 + (k % 10) as f32 * 25.0,
+                200.0 + (k / 10) as f32 * 25.0,
+            ]);
+            radii.push(3.0);
+        }
+        let positions = vec![10.0, 10.0, 100.0, 100.0, 250.0, 240.0];
+        let velocities = vec![5.0, 0.0, 1.0, 1.0, -3.0, 4.0];
+
+        let mut grid = SpatialGrid::new(20.0);
+        grid.rebuild(&obstacles, 500.0, 500.0);
+
+        let mut brute = vec![0.0; 6];
+        let mut fast = vec![0.0; 6];
+        avoid_obstacles_all(
+            &positions,
+            &velocities,
+            &mut brute,
+            &obstacles,
+            &radii,
+            2.0,
+            1.0,
+        );
+        avoid_obstacles_grid(
+            &positions,
+            &velocities,
+            &mut fast,
+            &obstacles,
+            &radii,
+            &grid,
+            2.0,
+            1.0,
+        );
+
+        assert_eq!(fast, brute);
+        assert_ne!(&fast[..2], &[0.0, 0.0]);
+        assert_eq!(&fast[2..4], &[0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_raycast_tangent_and_through() {
+        let obstacles = vec![5.0, 0.0];
+        let radii = vec![1.0];
+
+        // Grazes the top of the circle at x = 5
+        let tangent = raycast_obstacles(0.0, 1.0, 1.0, 0.0, 20.0, &obstacles, &radii);
+        assert_eq!(tangent, 5.0);
+
+        let through = raycast_obstacles(0.0, 0.0, 2.0, 0.0, 20.0, &obstacles, &radii);
+        assert_eq!(through, 4.0);
+
+        let behind = raycast_obstacles(0.0, 0.0, -1.0, 0.0, 20.0, &obstacles, &radii);
+        let above = raycast_obstacles(0.0, 1.5, 1.0, 0.0, 20.0, &obstacles, &radii);
+        assert_eq!(behind, 20.0);
+        assert_eq!(above, 20.0);
+    }
+}
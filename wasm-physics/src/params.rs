@@ -0,0 +1,223 @@
+//! Simulation tuning parameters shared with JS.
+
+use wasm_bindgen::prelude::*;
+
+/// Tuning knobs for the combined simulation step.
+///
+/// Every field is a JS property. Setters sanitize their input: NaN and
+/// infinite values are ignored, keeping the previous value; radii, speeds,
+/// `dt` and world size are clamped to be non-negative and `drag` to
+/// `[0, 1]`, so a bad slider value cannot produce NaN velocities.
+/// Defaults mirror the simulation's default configuration.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug)]
+pub struct SimParams {
+    pub(crate) separation_radius: f32,
+    pub(crate) alignment_radius: f32,
+    pub(crate) cohesion_radius: f32,
+    pub(crate) separation_weight: f32,
+    pub(crate) alignment_weight: f32,
+    pub(crate) cohesion_weight: f32,
+    pub(crate) min_speed: f32,
+    pub(crate) max_speed: f32,
+    pub(crate) drag: f32,
+    pub(crate) dt: f32,
+    pub(crate) width: f32,
+    pub(crate) height: f32,
+}
+
+#[wasm_bindgen]
+impl SimParams {
+    /// Create parameters with default values.
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> SimParams {
+        SimParams {
+            separation_radius: 25.0,
+            alignment_radius: 25.0,
+            cohesion_radius: 25.0,
+            separation_weight: 1.1,
+            alignment_weight: 1.1,
+            cohesion_weight: 1.0,
+            min_speed: 1.0,
+            max_speed: 4.0,
+            drag: 0.005,
+            dt: 1.0,
+            width: 800.0,
+            height: 600.0,
+        }
+    }
+
+    /// Neighbor radius for separation.
+    #[wasm_bindgen(getter)]
+    pub fn separation_radius(&self) -> f32 {
+        self.separation_radius
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_separation_radius(&mut self, v: f32) {
+        self.separation_radius = finite_or(v, self.separation_radius).max(0.0);
+    }
+
+    /// Neighbor radius for alignment.
+    #[wasm_bindgen(getter)]
+    pub fn alignment_radius(&self) -> f32 {
+        self.alignment_radius
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_alignment_radius(&mut self, v: f32) {
+        self.alignment_radius = finite_or(v, self.alignment_radius).max(0.0);
+    }
+
+    /// Neighbor radius for cohesion.
+    #[wasm_bindgen(getter)]
+    pub fn cohesion_radius(&self) -> f32 {
+        self.cohesion_radius
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_cohesion_radius(&mut self, v: f32) {
+        self.cohesion_radius = finite_or(v, self.cohesion_radius).max(0.0);
+    }
+
+    /// Separation force multiplier.
+    #[wasm_bindgen(getter)]
+    pub fn separation_weight(&self) -> f32 {
+        self.separation_weight
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_separation_weight(&mut self, v: f32) {
+        self.separation_weight = finite_or(v, self.separation_weight);
+    }
+
+    /// Alignment force multiplier.
+    #[wasm_bindgen(getter)]
+    pub fn alignment_weight(&self) -> f32 {
+        self.alignment_weight
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_alignment_weight(&mut self, v: f32) {
+        self.alignment_weight = finite_or(v, self.alignment_weight);
+    }
+
+    /// Cohesion force magnitude.
+    #[wasm_bindgen(getter)]
+    pub fn cohesion_weight(&self) -> f32 {
+        self.cohesion_weight
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_cohesion_weight(&mut self, v: f32) {
+        self.cohesion_weight = finite_or(v, self.cohesion_weight);
+    }
+
+    /// Minimum speed.
+    #[wasm_bindgen(getter)]
+    pub fn min_speed(&self) -> f32 {
+        self.min_speed
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_min_speed(&mut self, v: f32) {
+        self.min_speed = finite_or(v, self.min_speed).max(0.0);
+    }
+
+    /// Maximum speed.
+    #[wasm_bindgen(getter)]
+    pub fn max_speed(&self) -> f32 {
+        self.max_speed
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_max_speed(&mut self, v: f32) {
+        self.max_speed = finite_or(v, self.max_speed).max(0.0);
+    }
+
+    /// Drag coefficient (0-1).
+    #[wasm_bindgen(getter)]
+    pub fn drag(&self) -> f32 {
+        self.drag
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_drag(&mut self, v: f32) {
+        self.drag = finite_or(v, self.drag).clamp(0.0, 1.0);
+    }
+
+    /// Delta time per step.
+    #[wasm_bindgen(getter)]
+    pub fn dt(&self) -> f32 {
+        self.dt
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_dt(&mut self, v: f32) {
+        self.dt = finite_or(v, self.dt).max(0.0);
+    }
+
+    /// World width.
+    #[wasm_bindgen(getter)]
+    pub fn width(&self) -> f32 {
+        self.width
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_width(&mut self, v: f32) {
+        self.width = finite_or(v, self.width).max(0.0);
+    }
+
+    /// World height.
+    #[wasm_bindgen(getter)]
+    pub fn height(&self) -> f32 {
+        self.height
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_height(&mut self, v: f32) {
+        self.height = finite_or(v, self.height).max(0.0);
+    }
+}
+
+/// `value` if it is finite, otherwise `previous`.
+fn finite_or(value: f32, previous: f32) -> f32 {
+    if value.is_finite() {
+        value
+    } else {
+        previous
+    }
+}
+
+impl Default for SimParams {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_params_setter_round_trip() {
+        let mut params = SimParams::new();
+        assert_eq!(params.cohesion_weight(), 1.0);
+
+        params.set_cohesion_weight(2.5);
+        assert_eq!(params.cohesion_weight(), 2.5);
+
+        params.set_drag(1.5);
+        assert_eq!(params.drag(), 1.0);
+        params.set_separation_radius(-3.0);
+        assert_eq!(params.separation_radius(), 0.0);
+
+        // Non-finite input keeps the previous value
+        params.set_drag(f32::NAN);
+        assert_eq!(params.drag(), 1.0);
+        params.set_cohesion_weight(f32::NAN);
+        assert_eq!(params.cohesion_weight(), 2.5);
+        params.set_max_speed(f32::INFINITY);
+        assert_eq!(params.max_speed(), 4.0);
+    }
+}
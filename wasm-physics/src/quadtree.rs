@@ -0,0 +1,238 @@
+//! Quadtree spatial index for clustered flocks.
+//!
+//! A uniform [`SpatialGrid`](crate::SpatialGrid) wastes time on empty cells
+//! and overflows in dense ones when boids bunch up; the quadtree subdivides
+//! only where boids actually are.
+
+use wasm_bindgen::prelude::*;
+
+use crate::read_pair;
+
+/// Nodes stop splitting at this depth so coincident boids cannot recurse
+/// forever.
+const MAX_DEPTH: u32 = 16;
+
+struct Node {
+    min_x: f32,
+    min_y: f32,
+    max_x: f32,
+    max_y: f32,
+    /// Index of the first of four consecutive children, if split.
+    children: Option<usize>,
+    items: Vec<u32>,
+}
+
+impl Node {
+    fn new(min_x: f32, min_y: f32, max_x: f32, max_y: f32) -> Node {
+        Node {
+            min_x,
+            min_y,
+            max_x,
+            max_y,
+            children: None,
+            items: Vec::new(),
+        }
+    }
+}
+
+/// Point quadtree over boid positions.
+#[wasm_bindgen]
+pub struct QuadTree {
+    nodes: Vec<Node>,
+    positions: Vec<f32>,
+    max_per_node: usize,
+}
+
+#[wasm_bindgen]
+impl QuadTree {
+    /// Build a quadtree over the given positions.
+    ///
+    /// The root covers the world rectangle (spanning from the origin, so a
+    /// negative size extends it the other way), grown to include any boids
+    /// outside it, and nodes split until they hold at most `max_per_node`.
+    ///
+    /// # Arguments
+    /// * `positions` - Array of positions (x, y pairs)
+    /// * `bounds_width` - World width
+    /// * `bounds_height` - World height
+    /// * `max_per_node` - Leaf capacity before splitting
+    pub fn build(
+        positions: &[f32],
+        bounds_width: f32,
+        bounds_height: f32,
+        max_per_node: usize,
+    ) -> QuadTree {
+        let count = positions.len() / 2;
+        // Negative or non-finite sizes still give a well-formed root
+        let finite = |v: f32| if v.is_finite() { v } else { 0.0 };
+        let (w, h) = (finite(bounds_width), finite(bounds_height));
+        let (mut min_x, mut min_y, mut max_x, mut max_y) =
+            (w.min(0.0), h.min(0.0), w.max(0.0), h.max(0.0));
+        for i in 0..count {
+            let (x, y) = read_pair(positions, i);
+            min_x = min_x.min(x);
+            min_y = min_y.min(y);
+            max_x = max_x.max(x);
+            max_y = max_y.max(y);
+        }
+
+        let mut tree = QuadTree {
+            nodes: vec![Node::new(min_x, min_y, max_x, max_y)],
+            positions: positions.to_vec(),
+            max_per_node: max_per_node.max(1),
+        };
+        for i in 0..count {
+            tree.insert(i as u32);
+        }
+        tree
+    }
+
+    /// Indices of boids strictly within `radius` of (cx, cy).
+    pub fn query_range(&self, cx: f32, cy: f32, radius: f32) -> Vec<u32> {
+        let radius_sq = radius * radius;
+        let mut found = Vec::new();
+        let mut stack = vec![0usize];
+
+        while let Some(n) = stack.pop() {
+            let node = &self.nodes[n];
+
+            // Closest point of the node rectangle to the query center
+            let nx = cx.max(node.min_x).min(node.max_x);
+            let ny = cy.max(node.min_y).min(node.max_y);
+            if (nx - cx) * (nx - cx) + (ny - cy) * (ny - cy) > radius_sq {
+                continue;
+            }
+
+            match node.children {
+                Some(first) => stack.extend(first..first + 4),
+                None => {
+                    for &j in &node.items {
+                        let (x, y) = read_pair(&self.positions, j as usize);
+                        if (x - cx) * (x - cx) + (y - cy) * (y - cy) < radius_sq {
+                            found.push(j);
+                        }
+                    }
+                }
+            }
+        }
+
+        found
+    }
+}
+
+impl QuadTree {
+    fn insert(&mut self, item: u32) {
+        let (x, y) = read_pair(&self.positions, item as usize);
+        let mut n = 0;
+        let mut depth = 0;
+
+        while let Some(first) = self.nodes[n].children {
+            n = first + self.quadrant(n, x, y);
+            depth += 1;
+        }
+
+        self.nodes[n].items.push(item);
+
+        if self.nodes[n].items.len() > self.max_per_node && depth < MAX_DEPTH {
+            self.split(n, depth);
+        }
+    }
+
+    fn split(&mut self, n: usize, depth: u32) {
+        let Node {
+            min_x,
+            min_y,
+            max_x,
+            max_y,
+            ..
+        } = self.nodes[n];
+        let mid_x = (min_x + max_x) * 0.5;
+        let mid_y = (min_y + max_y) * 0.5;
+
+        let first = self.nodes.len();
+        self.nodes.push(Node::new(min_x, min_y, mid_x, mid_y));
+        self.nodes.push(Node::new(mid_x, min_y, max_x, mid_y));
+        self.nodes.push(Node::new(min_x, mid_y, mid_x, max_y));
+        self.nodes.push(Node::new(mid_x, mid_y, max_x, max_y));
+        self.nodes[n].children = Some(first);
+
+        for item in std::mem::take(&mut self.nodes[n].items) {
+            let (x, y) = read_pair(&self.positions, item as usize);
+            let child = first + self.quadrant(n, x, y);
+            self.nodes[child].items.push(item);
+        }
+
+        // Everything may have landed in one quadrant
+        if depth + 1 < MAX_DEPTH {
+            for child in first..first + 4 {
+                if self.nodes[child].items.len() > self.max_per_node {
+                    self.split(child, depth + 1);
+                }
+            }
+        }
+    }
+
+    fn quadrant(&self, n: usize, x: f32, y: f32) -> usize {
+        let node = &self.nodes[n];
+        let right = x >= (node.min_x + node.max_x) * 0.5;
+        let bottom = y >= (node.min_y + node.max_y) * 0.5;
+        right as usize + 2 * bottom as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quadtree_matches_brute_force_clustered() {
+        let mut seed = 99u32;
+        let mut next = || {
+            seed = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+            (seed >> 8) as f32 / (1u32 << 24) as f32
+        };
+
+        // Three tight clusters plus sparse background
+        let centers = [(100.0, 100.0), (400.0, 250.0), (250.0, 420.0)];
+        let mut positions = Vec::new();
+        for i in 0..500 {
+            if i % 10 == 0 {
+                positions.push(next() * 500.0);
+                positions.push(next() * 500.0);
+            } else {
+                let (cx, cy) = centers[i % 3];
+                positions.push(cx + (next() + next() - 1.0) * 15.0);
+                positions.push(cy + (next() + next() - 1.0) * 15.0);
+            }
+        }
+
+        let tree = QuadTree::build(&positions, 500.0, 500.0, 8);
+
+        for q in 0..100 {
+            let (cx, cy) = read_pair(&positions, q * 5);
+            let radius = 5.0 + (q % 7) as f32 * 6.0;
+
+            let mut from_tree = tree.query_range(cx, cy, radius);
+            from_tree.sort_unstable();
+            let brute: Vec<u32> = (0..500)
+                .filter(|&j| {
+                    let (x, y) = read_pair(&positions, j);
+                    (x - cx) * (x - cx) + (y - cy) * (y - cy) < radius * radius
+                })
+                .map(|j| j as u32)
+                .collect();
+
+            assert_eq!(from_tree, brute);
+        }
+    }
+
+    #[test]
+    fn test_degenerate_bounds_do_not_panic() {
+        let tree = QuadTree::build(&[], -1.0, -1.0, 8);
+        assert!(tree.query_range(0.0, 0.0, 5.0).is_empty());
+
+        let positions = vec![-0.5, -0.5, 3.0, 3.0];
+        let tree = QuadTree::build(&positions, f32::NAN, -1.0, 1);
+        assert_eq!(tree.query_range(0.0, 0.0, 1.0), vec![0]);
+    }
+}
@@ -0,0 +1,119 @@
+//! Helpers for the renderer.
+
+use wasm_bindgen::prelude::*;
+
+use crate::{read_pair, wrap_delta};
+
+/// Batch collect the boids inside a rectangle.
+///
+/// Writes the indices of boids within `[min_x, max_x] × [min_y, max_y]`
+/// into `out_visible`, in order, and returns how many were written (at most
+/// `out_visible.len()`).
+///
+/// # Arguments
+/// * `positions` - Array of positions (x, y pairs)
+/// * `min_x`, `min_y` - Top-left corner of the rectangle
+/// * `max_x`, `max_y` - Bottom-right corner of the rectangle
+/// * `out_visible` - Output array of boid indices
+#[wasm_bindgen]
+pub fn cull_to_rect(
+    positions: &[f32],
+    min_x: f32,
+    min_y: f32,
+    max_x: f32,
+    max_y: f32,
+    out_visible: &mut [u32],
+) -> usize {
+    let mut written = 0;
+
+    for i in 0..positions.len() / 2 {
+        if written == out_visible.len() {
+            break;
+        }
+
+        let (x, y) = read_pair(positions, i);
+        if x >= min_x && x <= max_x && y >= min_y && y <= max_y {
+            out_visible[written] = i as u32;
+            written += 1;
+        }
+    }
+
+    written
+}
+
+/// Batch linearly interpolate between two position buffers.
+///
+/// Writes `a + t * (b - a)` per element, for rendering between physics
+/// steps. Processes as many elements as all three arrays hold.
+///
+/// # Arguments
+/// * `a` - Previous positions (x, y pairs)
+/// * `b` - Current positions (x, y pairs)
+/// * `t` - Blend factor, 0 = `a`, 1 = `b`
+/// * `out` - Output interpolated positions (x, y pairs)
+#[wasm_bindgen]
+pub fn lerp_positions(a: &[f32], b: &[f32], t: f32, out: &mut [f32]) {
+    for ((o, &from), &to) in out.iter_mut().zip(a).zip(b) {
+        *o = from + t * (to - from);
+    }
+}
+
+/// Wrap-aware version of [`lerp_positions`] for toroidal worlds.
+///
+/// Interpolates along the shortest path around the seam, so a boid that
+/// wrapped from one edge to the other between steps does not streak across
+/// the screen. Results are wrapped back into `[0, width) × [0, height)`.
+///
+/// # Arguments
+/// * `a` - Previous positions (x, y pairs)
+/// * `b` - Current positions (x, y pairs)
+/// * `t` - Blend factor, 0 = `a`, 1 = `b`
+/// * `width` - World width
+/// * `height` - World height
+/// * `out` - Output interpolated positions (x, y pairs)
+#[wasm_bindgen]
+pub fn lerp_positions_wrapped(
+    a: &[f32],
+    b: &[f32],
+    t: f32,
+    width: f32,
+    height: f32,
+    out: &mut [f32],
+) {
+    for (i, ((o, &from), &to)) in out.iter_mut().zip(a).zip(b).enumerate() {
+        let size = if i % 2 == 0 { width } else { height };
+        *o = (from + t * wrap_delta(to - from, size)).rem_euclid(size);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cull_to_rect() {
+        let positions = vec![-5.0, 5.0, 10.0, 10.0, 200.0, 0.0, 50.0, 99.0, 50.0, 101.0];
+        let mut visible = vec![0; 5];
+
+        let count = cull_to_rect(&positions, 0.0, 0.0, 100.0, 100.0, &mut visible);
+
+        assert_eq!(count, 2);
+        assert_eq!(visible[..count], [1, 3]);
+    }
+
+    #[test]
+    fn test_lerp_positions() {
+        let a = vec![0.0, 10.0, 98.0, 50.0];
+        let b = vec![10.0, 20.0, 4.0, 50.0];
+        let mut out = vec![0.0; 4];
+
+        lerp_positions(&a, &b, 0.5, &mut out);
+        assert_eq!(&out[..2], &[5.0, 15.0]);
+
+        // 98 -> 4 crosses the seam of a 100-wide world: 3 units each way
+        lerp_positions_wrapped(&a, &b, 0.5, 100.0, 100.0, &mut out);
+        assert_eq!(&out[..2], &[5.0, 15.0]);
+        assert!((out[2] - 1.0).abs() < 1e-5);
+        assert_eq!(out[3], 50.0);
+    }
+}
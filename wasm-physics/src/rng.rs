@@ -0,0 +1,63 @@
+//! Small deterministic PRNGs.
+//!
+//! Simulations must be reproducible across runs and machines, so randomness
+//! comes from explicit xorshift state rather than a global generator.
+
+/// Advance a xorshift32 state and return the next value.
+///
+/// A zero state would get stuck at zero, so it is replaced by a fixed seed.
+#[inline]
+pub(crate) fn xorshift32(state: &mut u32) -> u32 {
+    let mut x = if *state == 0 { 0x9E37_79B9 } else { *state };
+    x ^= x << 13;
+    x ^= x >> 17;
+    x ^= x << 5;
+    *state = x;
+    x
+}
+
+/// Next uniform value in `[0, 1)` from a xorshift32 state.
+#[inline]
+pub(crate) fn next_unit(state: &mut u32) -> f32 {
+    // Top 24 bits fill the f32 mantissa exactly
+    (xorshift32(state) >> 8) as f32 * (1.0 / (1u32 << 24) as f32)
+}
+
+/// Fold a 64-bit seed into a xorshift32 state.
+///
+/// Both halves contribute, so seeds differing only in the high bits still
+/// give different streams.
+#[inline]
+pub(crate) fn state_from_seed(seed: u64) -> u32 {
+    // splitmix64 finalizer
+    let mut z = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^= z >> 31;
+    (z ^ (z >> 32)) as u32
+}
+
+/// Approximately standard-normal value from a xorshift32 state.
+///
+/// Sums four uniforms (Irwin–Hall) and rescales to zero mean and unit
+/// variance; the tails are cut off at ±2√3.
+#[inline]
+pub(crate) fn next_gaussian(state: &mut u32) -> f32 {
+    let sum: f32 = (0..4).map(|_| next_unit(state)).sum();
+    (sum - 2.0) * 3.0f32.sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_unit_range() {
+        let mut state = 0;
+        for _ in 0..10_000 {
+            let u = next_unit(&mut state);
+            assert!((0.0..1.0).contains(&u));
+        }
+        assert_ne!(state, 0);
+    }
+}
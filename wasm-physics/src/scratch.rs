@@ -0,0 +1,52 @@
+//! Reusable temporaries for the grid force functions.
+//!
+//! Allocating inside a per-frame loop is slow in WASM. A `Scratch` is
+//! created once and passed into the `compute_*_grid` functions, which
+//! accumulate per-boid sums into it and only grow it when the flock does.
+
+use wasm_bindgen::prelude::*;
+
+/// Per-boid force accumulators, reused across calls.
+///
+/// The grid force functions visit each pair of neighbors once and add the
+/// pair's contribution to both boids, so a boid's sum is only complete once
+/// every pair has been seen; these buffers hold the sums until then.
+#[wasm_bindgen]
+pub struct Scratch {
+    sums: Vec<f32>,
+    weights: Vec<f32>,
+}
+
+#[wasm_bindgen]
+impl Scratch {
+    /// Allocate accumulators for `count` boids.
+    #[wasm_bindgen(constructor)]
+    pub fn new(count: usize) -> Scratch {
+        Scratch {
+            sums: vec![0.0; count * 2],
+            weights: vec![0.0; count],
+        }
+    }
+
+    /// Number of boids the accumulators are sized for.
+    pub fn len(&self) -> usize {
+        self.weights.len()
+    }
+
+    /// Whether the accumulators are sized for no boids.
+    pub fn is_empty(&self) -> bool {
+        self.weights.is_empty()
+    }
+}
+
+impl Scratch {
+    /// Zeroed sums (x, y pairs) and weights for `count` boids, growing the
+    /// buffers only if the flock did.
+    pub(crate) fn reset(&mut self, count: usize) -> (&mut [f32], &mut [f32]) {
+        self.sums.clear();
+        self.sums.resize(count * 2, 0.0);
+        self.weights.clear();
+        self.weights.resize(count, 0.0);
+        (&mut self.sums, &mut self.weights)
+    }
+}
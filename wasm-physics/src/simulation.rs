@@ -0,0 +1,200 @@
+//! Combined per-frame pipeline.
+//!
+//! Calling each batch function from JS costs a boundary crossing apiece;
+//! these entry points run a whole frame in one call.
+
+use wasm_bindgen::prelude::*;
+
+use crate::buffers::FlockBuffers;
+use crate::params::SimParams;
+use crate::{
+    compute_alignment, compute_cohesion, compute_separation, integrate_all,
+    reset_accelerations_all, wrap_positions_all, FALLOFF_CONSTANT, FALLOFF_INVERSE,
+};
+
+/// Run one full simulation step.
+///
+/// Resets accelerations, accumulates weighted separation, alignment and
+/// cohesion, integrates, and wraps positions to the world.
+///
+/// # Arguments
+/// * `positions` - Mutable array of positions (x, y pairs)
+/// * `velocities` - Mutable array of velocities (x, y pairs)
+/// * `accelerations` - Mutable scratch array of accelerations (x, y pairs)
+/// * `params` - Simulation parameters
+#[wasm_bindgen]
+pub fn simulate_step(
+    positions: &mut [f32],
+    velocities: &mut [f32],
+    accelerations: &mut [f32],
+    params: &SimParams,
+) {
+    reset_accelerations_all(accelerations);
+    compute_separation(
+        positions,
+        accelerations,
+        params.separation_radius,
+        params.separation_weight,
+        FALLOFF_INVERSE,
+    );
+    compute_alignment(
+        positions,
+        velocities,
+        accelerations,
+        params.alignment_radius,
+        params.alignment_weight,
+        FALLOFF_CONSTANT,
+    );
+    compute_cohesion(
+        positions,
+        accelerations,
+        params.cohesion_radius,
+        params.cohesion_weight,
+        false,
+        FALLOFF_CONSTANT,
+    );
+    integrate_all(
+        positions,
+        velocities,
+        accelerations,
+        params.dt,
+        params.min_speed,
+        params.max_speed,
+        params.drag,
+    );
+    wrap_positions_all(positions, params.width, params.height);
+}
+
+/// Most substeps [`simulate_steps`] runs for one frame.
+pub const MAX_SUBSTEPS: u32 = 16;
+
+/// Advance a flock by one render frame using fixed substeps.
+///
+/// `frame_dt` is split into the fewest equal substeps no longer than
+/// `max_substep`, each running the full [`simulate_step`] pipeline, so
+/// stability no longer depends on the render framerate. `params.dt` is
+/// ignored in favor of the substep length. The buffers' previous positions
+/// are those from the start of the frame, not of the last substep.
+///
+/// At most [`MAX_SUBSTEPS`] substeps of `max_substep` are run; beyond that
+/// the rest of the frame is dropped, so a long stall (e.g. a background tab
+/// resuming) slows the simulation down instead of hanging the thread.
+/// Frames with a non-positive or non-finite `frame_dt` are skipped.
+///
+/// # Arguments
+/// * `buffers` - Flock state to advance
+/// * `params` - Simulation parameters
+/// * `frame_dt` - Elapsed frame time
+/// * `max_substep` - Longest allowed substep; `<= 0` runs a single step
+#[wasm_bindgen]
+pub fn simulate_steps(
+    buffers: &mut FlockBuffers,
+    params: &SimParams,
+    frame_dt: f32,
+    max_substep: f32,
+) {
+    if !frame_dt.is_finite() || frame_dt <= 0.0 {
+        return;
+    }
+
+    // Shave off rounding so e.g. 0.1 / 0.02 gives 5 substeps, not 6
+    let needed = if max_substep > 0.0 {
+        ((frame_dt / max_substep) * (1.0 - 1e-5)).ceil().max(1.0)
+    } else {
+        1.0
+    };
+
+    let mut substep_params = *params;
+    let substeps = if needed > MAX_SUBSTEPS as f32 {
+        substep_params.dt = max_substep;
+        MAX_SUBSTEPS
+    } else {
+        substep_params.dt = frame_dt / needed;
+        needed as u32
+    };
+    buffers.save_prev_positions();
+    for _ in 0..substeps {
+        buffers.advance(&substep_params);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_simulate_step_matches_manual_sequence() {
+        let params = SimParams::new();
+        let positions = vec![10.0, 10.0, 20.0, 12.0, 15.0, 30.0, 795.0, 2.0];
+        let velocities = vec![1.0, 0.0, 0.0, 2.0, -1.0, 1.0, 3.0, -3.0];
+
+        let mut pos_a = positions.clone();
+        let mut vel_a = velocities.clone();
+        let mut acc_a = vec![5.0; 8];
+        simulate_step(&mut pos_a, &mut vel_a, &mut acc_a, &params);
+
+        let mut pos_b = positions;
+        let mut vel_b = velocities;
+        let mut acc_b = vec![0.0; 8];
+        compute_separation(&pos_b, &mut acc_b, 25.0, 1.1, FALLOFF_INVERSE);
+        compute_alignment(&pos_b, &vel_b, &mut acc_b, 25.0, 1.1, FALLOFF_CONSTANT);
+        compute_cohesion(&pos_b, &mut acc_b, 25.0, 1.0, false, FALLOFF_CONSTANT);
+        integrate_all(&mut pos_b, &mut vel_b, &acc_b, 1.0, 1.0, 4.0, 0.005);
+        wrap_positions_all(&mut pos_b, 800.0, 600.0);
+
+        assert_eq!(pos_a, pos_b);
+        assert_eq!(vel_a, vel_b);
+        assert_eq!(acc_a, acc_b);
+    }
+
+    #[test]
+    fn test_simulate_steps_substeps() {
+        let positions: Vec<f32> = (0..20).map(|i| 200.0 + i as f32 * 7.0).collect();
+        let velocities: Vec<f32> = (0..20).map(|i| (i as f32).cos() * 3.0).collect();
+        let params = SimParams::new();
+
+        let mut buffers = FlockBuffers::new(10);
+        buffers.set_positions(&positions);
+        buffers.set_velocities(&velocities);
+        simulate_steps(&mut buffers, &params, 0.1, 0.02);
+
+        // Exactly five substeps of 0.1 / 5; any other count would differ
+        let mut manual = FlockBuffers::new(10);
+        manual.set_positions(&positions);
+        manual.set_velocities(&velocities);
+        let mut fixed = params;
+        fixed.set_dt(0.1 / 5.0);
+        for _ in 0..5 {
+            manual.step(&fixed);
+        }
+
+        assert!(buffers.positions().iter().all(|p| p.is_finite()));
+        assert_eq!(buffers.positions(), manual.positions());
+    }
+
+    #[test]
+    fn test_simulate_steps_caps_substeps() {
+        let mut buffers = FlockBuffers::new(4);
+        buffers.set_velocities(&[1.0, 0.0, 0.0, 1.0, -1.0, 0.0, 0.0, -1.0]);
+        let params = SimParams::new();
+
+        let mut manual = FlockBuffers::new(4);
+        manual.set_velocities(buffers.velocities());
+        let mut fixed = params;
+        fixed.set_dt(0.01);
+        for _ in 0..MAX_SUBSTEPS {
+            manual.step(&fixed);
+        }
+
+        // An hour-long frame only runs the capped substeps
+        simulate_steps(&mut buffers, &params, 3600.0, 0.01);
+        assert_eq!(buffers.positions(), manual.positions());
+
+        // Non-finite frames are skipped, with or without a substep limit
+        let before = buffers.positions().to_vec();
+        simulate_steps(&mut buffers, &params, f32::INFINITY, 1e-9);
+        simulate_steps(&mut buffers, &params, f32::INFINITY, 0.0);
+        simulate_steps(&mut buffers, &params, f32::NAN, 0.01);
+        assert_eq!(buffers.positions(), before);
+    }
+}
@@ -0,0 +1,145 @@
+//! Save and restore flock state as bytes.
+//!
+//! Layout, all little-endian: the magic `BOID`, a `u32` format version, a
+//! `u32` boid count, then `count` position pairs followed by `count`
+//! velocity pairs as `f32`.
+
+use wasm_bindgen::prelude::*;
+
+const MAGIC: [u8; 4] = *b"BOID";
+const VERSION: u32 = 1;
+const HEADER_LEN: usize = 12;
+
+/// Serialize positions and velocities into a versioned byte buffer.
+///
+/// Stores as many boids as both arrays hold.
+///
+/// # Arguments
+/// * `positions` - Array of positions (x, y pairs)
+/// * `velocities` - Array of velocities (x, y pairs)
+#[wasm_bindgen]
+pub fn serialize_state(positions: &[f32], velocities: &[f32]) -> Vec<u8> {
+    let count = (positions.len() / 2).min(velocities.len() / 2);
+    let mut bytes = Vec::with_capacity(HEADER_LEN + count * 16);
+
+    bytes.extend_from_slice(&MAGIC);
+    bytes.extend_from_slice(&VERSION.to_le_bytes());
+    bytes.extend_from_slice(&(count as u32).to_le_bytes());
+    for v in positions[..count * 2]
+        .iter()
+        .chain(&velocities[..count * 2])
+    {
+        bytes.extend_from_slice(&v.to_le_bytes());
+    }
+
+    bytes
+}
+
+/// Restore positions and velocities written by [`serialize_state`].
+///
+/// Returns the number of boids restored. Fails without writing anything on
+/// a bad magic or version, truncated data, or output arrays too small for
+/// the stored flock.
+///
+/// # Arguments
+/// * `bytes` - Serialized state
+/// * `positions` - Mutable array of positions to fill (x, y pairs)
+/// * `velocities` - Mutable array of velocities to fill (x, y pairs)
+#[wasm_bindgen]
+pub fn deserialize_state(
+    bytes: &[u8],
+    positions: &mut [f32],
+    velocities: &mut [f32],
+) -> Result<usize, JsValue> {
+    decode_state(bytes, positions, velocities).map_err(|e| JsValue::from_str(&e))
+}
+
+/// [`deserialize_state`] with a plain string error.
+pub(crate) fn decode_state(
+    bytes: &[u8],
+    positions: &mut [f32],
+    velocities: &mut [f32],
+) -> Result<usize, String> {
+    if bytes.len() < HEADER_LEN {
+        return Err(format!(
+            "state is {} bytes, shorter than the header",
+            bytes.len()
+        ));
+    }
+    if bytes[..4] != MAGIC {
+        return Err("not a boid state buffer (bad magic)".to_string());
+    }
+
+    let word =
+        |at: usize| u32::from_le_bytes([bytes[at], bytes[at + 1], bytes[at + 2], bytes[at + 3]]);
+    let version = word(4);
+    if version != VERSION {
+        return Err(format!(
+            "unsupported state version {version}, expected {VERSION}"
+        ));
+    }
+
+    let count = word(8) as usize;
+    let expected = count
+        .checked_mul(16)
+        .and_then(|n| n.checked_add(HEADER_LEN));
+    if expected != Some(bytes.len()) {
+        return Err(format!(
+            "state for {count} boids does not match its {} byte length",
+            bytes.len()
+        ));
+    }
+    let floats = count * 2;
+    if positions.len() < floats || velocities.len() < floats {
+        return Err(format!("output arrays are too small for {count} boids"));
+    }
+
+    let mut values = bytes[HEADER_LEN..]
+        .chunks_exact(4)
+        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]));
+    for (out, v) in positions[..floats].iter_mut().zip(&mut values) {
+        *out = v;
+    }
+    for (out, v) in velocities[..floats].iter_mut().zip(&mut values) {
+        *out = v;
+    }
+
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_state_round_trip() {
+        let positions = vec![1.5, -2.0, 300.25, 0.0];
+        let velocities = vec![0.1, 0.2, -3.0, f32::MIN_POSITIVE];
+
+        let bytes = serialize_state(&positions, &velocities);
+        assert_eq!(&bytes[..4], b"BOID");
+
+        let mut pos_out = vec![0.0; 6];
+        let mut vel_out = vec![0.0; 6];
+        let count = decode_state(&bytes, &mut pos_out, &mut vel_out).unwrap();
+
+        assert_eq!(count, 2);
+        assert_eq!(pos_out[..4], positions[..]);
+        assert_eq!(vel_out[..4], velocities[..]);
+    }
+
+    #[test]
+    fn test_state_rejects_bad_header() {
+        let mut bytes = serialize_state(&[1.0, 2.0], &[3.0, 4.0]);
+        let mut pos = vec![0.0; 2];
+        let mut vel = vec![0.0; 2];
+
+        bytes[4] = 9;
+        let err = decode_state(&bytes, &mut pos, &mut vel).unwrap_err();
+        assert!(err.contains("version 9"));
+
+        bytes[0] = b'X';
+        assert!(decode_state(&bytes, &mut pos, &mut vel).is_err());
+        assert_eq!(pos, vec![0.0; 2]);
+    }
+}
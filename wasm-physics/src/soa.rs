@@ -0,0 +1,309 @@
+//! Structure-of-arrays variants of the batch functions.
+//!
+//! Interleaved (x, y) storage needs shuffles to line up lanes; with separate
+//! x and y arrays four boids fill one `f32x4` directly.
+
+use wasm_bindgen::prelude::*;
+
+use crate::{fallback_heading, SPEED_EPSILON};
+
+/// SoA version of [`integrate_all`](crate::integrate_all).
+///
+/// Processes as many boids as the shortest position/velocity array holds;
+/// missing accelerations count as zero.
+///
+/// # Arguments
+/// * `pos_x`, `pos_y` - Mutable position components
+/// * `vel_x`, `vel_y` - Mutable velocity components
+/// * `acc_x`, `acc_y` - Acceleration components
+/// * `dt` - Delta time
+/// * `min_speed` - Minimum speed
+/// * `max_speed` - Maximum speed
+/// * `drag` - Drag coefficient
+#[wasm_bindgen]
+#[allow(clippy::too_many_arguments)]
+pub fn integrate_all_soa(
+    pos_x: &mut [f32],
+    pos_y: &mut [f32],
+    vel_x: &mut [f32],
+    vel_y: &mut [f32],
+    acc_x: &[f32],
+    acc_y: &[f32],
+    dt: f32,
+    min_speed: f32,
+    max_speed: f32,
+    drag: f32,
+) {
+    let count = pos_x
+        .len()
+        .min(pos_y.len())
+        .min(vel_x.len())
+        .min(vel_y.len());
+
+    #[cfg(target_feature = "simd128")]
+    let start = if crate::simd_enabled() {
+        let simd_count = count.min(acc_x.len()).min(acc_y.len());
+        integrate_soa_simd(
+            pos_x, pos_y, vel_x, vel_y, acc_x, acc_y, simd_count, dt, min_speed, max_speed, drag,
+        )
+    } else {
+        0
+    };
+    #[cfg(not(target_feature = "simd128"))]
+    let start = 0;
+
+    let drag_factor = 1.0 - drag;
+    let min_sq = min_speed * min_speed;
+    let max_sq = max_speed * max_speed;
+
+    for i in start..count {
+        let ax = acc_x.get(i).copied().unwrap_or(0.0);
+        let ay = acc_y.get(i).copied().unwrap_or(0.0);
+        let mut vx = (vel_x[i] + ax * dt) * drag_factor;
+        let mut vy = (vel_y[i] + ay * dt) * drag_factor;
+
+        let speed_sq = vx * vx + vy * vy;
+        if speed_sq > max_sq {
+            let scale = max_speed / speed_sq.sqrt();
+            vx *= scale;
+            vy *= scale;
+        } else if speed_sq < min_sq {
+            if speed_sq > SPEED_EPSILON {
+                let scale = min_speed / speed_sq.sqrt();
+                vx *= scale;
+                vy *= scale;
+            } else {
+                let (hx, hy) = fallback_heading(i);
+                vx = hx * min_speed;
+                vy = hy * min_speed;
+            }
+        }
+
+        vel_x[i] = vx;
+        vel_y[i] = vy;
+        pos_x[i] += vx * dt;
+        pos_y[i] += vy * dt;
+    }
+}
+
+/// SIMD loop for [`integrate_all_soa`], four boids per iteration.
+///
+/// Returns the first boid left for the scalar tail.
+#[cfg(target_feature = "simd128")]
+#[allow(clippy::too_many_arguments)]
+fn integrate_soa_simd(
+    pos_x: &mut [f32],
+    pos_y: &mut [f32],
+    vel_x: &mut [f32],
+    vel_y: &mut [f32],
+    acc_x: &[f32],
+    acc_y: &[f32],
+    count: usize,
+    dt: f32,
+    min_speed: f32,
+    max_speed: f32,
+    drag: f32,
+) -> usize {
+    use core::arch::wasm32::*;
+
+    let chunks = count / 4;
+    let dt_v = f32x4_splat(dt);
+    let drag_v = f32x4_splat(1.0 - drag);
+    let min_speed_v = f32x4_splat(min_speed);
+    let max_speed_v = f32x4_splat(max_speed);
+    let min_sq_v = f32x4_splat(min_speed * min_speed);
+    let max_sq_v = f32x4_splat(max_speed * max_speed);
+    let eps_v = f32x4_splat(SPEED_EPSILON);
+    let one_v = f32x4_splat(1.0);
+
+    for c in 0..chunks {
+        let idx = c * 4;
+
+        // SAFETY: `idx + 4 <= count`, and `count` is bounded by every slice
+        // length; `v128_load`/`v128_store` have no alignment requirement.
+        unsafe {
+            let px_ptr = pos_x.as_mut_ptr().add(idx) as *mut v128;
+            let py_ptr = pos_y.as_mut_ptr().add(idx) as *mut v128;
+            let vx_ptr = vel_x.as_mut_ptr().add(idx) as *mut v128;
+            let vy_ptr = vel_y.as_mut_ptr().add(idx) as *mut v128;
+            let ax = v128_load(acc_x.as_ptr().add(idx) as *const v128);
+            let ay = v128_load(acc_y.as_ptr().add(idx) as *const v128);
+
+            let vx = f32x4_mul(f32x4_add(v128_load(vx_ptr), f32x4_mul(ax, dt_v)), drag_v);
+            let vy = f32x4_mul(f32x4_add(v128_load(vy_ptr), f32x4_mul(ay, dt_v)), drag_v);
+
+            let speed_sq = f32x4_add(f32x4_mul(vx, vx), f32x4_mul(vy, vy));
+            let speed = f32x4_sqrt(speed_sq);
+
+            let too_fast = f32x4_gt(speed_sq, max_sq_v);
+            let too_slow = v128_and(f32x4_lt(speed_sq, min_sq_v), f32x4_gt(speed_sq, eps_v));
+            let scale = v128_bitselect(
+                f32x4_div(max_speed_v, speed),
+                v128_bitselect(f32x4_div(min_speed_v, speed), one_v, too_slow),
+                too_fast,
+            );
+            let mut vx = f32x4_mul(vx, scale);
+            let mut vy = f32x4_mul(vy, scale);
+
+            let stalled = v128_and(f32x4_le(speed_sq, eps_v), f32x4_lt(speed_sq, min_sq_v));
+            if v128_any_true(stalled) {
+                let h: [(f32, f32); 4] = core::array::from_fn(|k| fallback_heading(idx + k));
+                let fx = f32x4_mul(f32x4(h[0].0, h[1].0, h[2].0, h[3].0), min_speed_v);
+                let fy = f32x4_mul(f32x4(h[0].1, h[1].1, h[2].1, h[3].1), min_speed_v);
+                vx = v128_bitselect(fx, vx, stalled);
+                vy = v128_bitselect(fy, vy, stalled);
+            }
+
+            v128_store(vx_ptr, vx);
+            v128_store(vy_ptr, vy);
+            v128_store(px_ptr, f32x4_add(v128_load(px_ptr), f32x4_mul(vx, dt_v)));
+            v128_store(py_ptr, f32x4_add(v128_load(py_ptr), f32x4_mul(vy, dt_v)));
+        }
+    }
+
+    chunks * 4
+}
+
+/// Split an interleaved (x, y) array into separate x and y arrays.
+///
+/// Converts as many boids as all three arrays can hold.
+///
+/// # Arguments
+/// * `src` - Interleaved array (x, y pairs)
+/// * `out_x` - Output x components
+/// * `out_y` - Output y components
+#[wasm_bindgen]
+pub fn deinterleave(src: &[f32], out_x: &mut [f32], out_y: &mut [f32]) {
+    let count = (src.len() / 2).min(out_x.len()).min(out_y.len());
+
+    #[cfg(target_feature = "simd128")]
+    let start = if crate::simd_enabled() {
+        use core::arch::wasm32::*;
+
+        let chunks = count / 4;
+        for c in 0..chunks {
+            // SAFETY: boids `4c..4c + 4` lie within `count`, so the eight
+            // source floats and four output floats per array are in bounds.
+            unsafe {
+                let a = v128_load(src.as_ptr().add(c * 8) as *const v128);
+                let b = v128_load(src.as_ptr().add(c * 8 + 4) as *const v128);
+                let xs = i32x4_shuffle::<0, 2, 4, 6>(a, b);
+                let ys = i32x4_shuffle::<1, 3, 5, 7>(a, b);
+                v128_store(out_x.as_mut_ptr().add(c * 4) as *mut v128, xs);
+                v128_store(out_y.as_mut_ptr().add(c * 4) as *mut v128, ys);
+            }
+        }
+        chunks * 4
+    } else {
+        0
+    };
+    #[cfg(not(target_feature = "simd128"))]
+    let start = 0;
+
+    for i in start..count {
+        out_x[i] = src[i * 2];
+        out_y[i] = src[i * 2 + 1];
+    }
+}
+
+/// Merge separate x and y arrays into an interleaved (x, y) array.
+///
+/// Converts as many boids as all three arrays can hold.
+///
+/// # Arguments
+/// * `x` - X components
+/// * `y` - Y components
+/// * `out` - Output interleaved array (x, y pairs)
+#[wasm_bindgen]
+pub fn interleave(x: &[f32], y: &[f32], out: &mut [f32]) {
+    let count = (out.len() / 2).min(x.len()).min(y.len());
+
+    #[cfg(target_feature = "simd128")]
+    let start = if crate::simd_enabled() {
+        use core::arch::wasm32::*;
+
+        let chunks = count / 4;
+        for c in 0..chunks {
+            // SAFETY: boids `4c..4c + 4` lie within `count`, so the four
+            // input floats per array and eight output floats are in bounds.
+            unsafe {
+                let xs = v128_load(x.as_ptr().add(c * 4) as *const v128);
+                let ys = v128_load(y.as_ptr().add(c * 4) as *const v128);
+                let lo = i32x4_shuffle::<0, 4, 1, 5>(xs, ys);
+                let hi = i32x4_shuffle::<2, 6, 3, 7>(xs, ys);
+                v128_store(out.as_mut_ptr().add(c * 8) as *mut v128, lo);
+                v128_store(out.as_mut_ptr().add(c * 8 + 4) as *mut v128, hi);
+            }
+        }
+        chunks * 4
+    } else {
+        0
+    };
+    #[cfg(not(target_feature = "simd128"))]
+    let start = 0;
+
+    for i in start..count {
+        out[i * 2] = x[i];
+        out[i * 2 + 1] = y[i];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::integrate_all;
+
+    #[test]
+    fn test_soa_matches_interleaved() {
+        let positions: Vec<f32> = (0..18).map(|i| i as f32 * 3.5).collect();
+        let velocities: Vec<f32> = (0..18)
+            .map(|i| match i % 5 {
+                0 => 12.0,
+                1 => -0.2,
+                2 => 0.0,
+                3 => 2.5,
+                _ => -1.0,
+            })
+            .collect();
+        let accelerations: Vec<f32> = (0..18).map(|i| (i as f32 * 0.7).sin()).collect();
+
+        let mut pos = positions.clone();
+        let mut vel = velocities.clone();
+        integrate_all(&mut pos, &mut vel, &accelerations, 0.1, 1.0, 5.0, 0.02);
+
+        let split = |v: &[f32]| -> (Vec<f32>, Vec<f32>) {
+            (
+                v.iter().step_by(2).copied().collect(),
+                v.iter().skip(1).step_by(2).copied().collect(),
+            )
+        };
+        let (mut px, mut py) = split(&positions);
+        let (mut vx, mut vy) = split(&velocities);
+        let (ax, ay) = split(&accelerations);
+        integrate_all_soa(
+            &mut px, &mut py, &mut vx, &mut vy, &ax, &ay, 0.1, 1.0, 5.0, 0.02,
+        );
+
+        for i in 0..9 {
+            assert!((px[i] - pos[i * 2]).abs() < 1e-5);
+            assert!((py[i] - pos[i * 2 + 1]).abs() < 1e-5);
+            assert!((vx[i] - vel[i * 2]).abs() < 1e-5);
+            assert!((vy[i] - vel[i * 2 + 1]).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn test_interleave_round_trip() {
+        let original: Vec<f32> = (0..20).map(|i| i as f32 * 1.25 - 7.0).collect();
+        let mut xs = vec![0.0; 10];
+        let mut ys = vec![0.0; 10];
+        let mut back = vec![0.0; 20];
+
+        deinterleave(&original, &mut xs, &mut ys);
+        assert_eq!(xs[3], original[6]);
+        assert_eq!(ys[3], original[7]);
+
+        interleave(&xs, &ys, &mut back);
+        assert_eq!(back, original);
+    }
+}
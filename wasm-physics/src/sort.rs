@@ -0,0 +1,127 @@
+//! Spatial reordering of boid buffers.
+//!
+//! Boids drift away from where they sit in memory, so neighbor loops end up
+//! jumping all over the arrays. Sorting along a Morton (Z-order) curve keeps
+//! spatially close boids close in memory.
+
+use wasm_bindgen::prelude::*;
+
+use crate::read_pair;
+
+/// Reorder boids along a Morton curve over the world rectangle.
+///
+/// Positions and velocities are permuted together, and `indices` is
+/// permuted the same way so JS can map each slot back to its boid ID
+/// (initialize it to `0..n` before the first sort). Only as many boids as
+/// all three arrays hold are sorted, so every boid keeps its own velocity
+/// and ID; any boids past that stay where they are. Boids outside the world
+/// use the nearest edge for their code; ties keep their current order.
+///
+/// # Arguments
+/// * `positions` - Mutable array of positions (x, y pairs)
+/// * `velocities` - Mutable array of velocities (x, y pairs)
+/// * `indices` - Mutable array of boid IDs (one per boid)
+/// * `width` - World width
+/// * `height` - World height
+#[wasm_bindgen]
+pub fn morton_sort(
+    positions: &mut [f32],
+    velocities: &mut [f32],
+    indices: &mut [u32],
+    width: f32,
+    height: f32,
+) {
+    let count = (positions.len() / 2)
+        .min(velocities.len() / 2)
+        .min(indices.len());
+
+    let mut order: Vec<(u32, usize)> = (0..count)
+        .map(|i| {
+            let (x, y) = read_pair(positions, i);
+            (morton_code(x, y, width, height), i)
+        })
+        .collect();
+    order.sort_by_key(|&(code, _)| code);
+
+    permute(positions, &order, 2);
+    permute(velocities, &order, 2);
+    permute(indices, &order, 1);
+}
+
+/// Interleave the bits of the quantized x and y coordinates.
+pub(crate) fn morton_code(x: f32, y: f32, width: f32, height: f32) -> u32 {
+    let quantize = |v: f32, size: f32| {
+        let t = if size > 0.0 { v / size } else { 0.0 };
+        (t.clamp(0.0, 1.0) * u16::MAX as f32) as u32
+    };
+
+    spread_bits(quantize(x, width)) | (spread_bits(quantize(y, height)) << 1)
+}
+
+/// Spread the low 16 bits of `v` into the even bit positions.
+fn spread_bits(mut v: u32) -> u32 {
+    v &= 0x0000_ffff;
+    v = (v | (v << 8)) & 0x00ff_00ff;
+    v = (v | (v << 4)) & 0x0f0f_0f0f;
+    v = (v | (v << 2)) & 0x3333_3333;
+    (v | (v << 1)) & 0x5555_5555
+}
+
+/// Rearrange `stride`-sized records so slot `k` receives record `order[k].1`.
+fn permute<T: Copy>(values: &mut [T], order: &[(u32, usize)], stride: usize) {
+    let src = values[..order.len() * stride].to_vec();
+
+    for (k, &(_, i)) in order.iter().enumerate() {
+        values[k * stride..(k + 1) * stride].copy_from_slice(&src[i * stride..(i + 1) * stride]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_morton_sort_permutation() {
+        let mut seed = 7u32;
+        let mut next = || {
+            seed = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+            (seed >> 8) as f32 / (1u32 << 24) as f32 * 100.0
+        };
+        let original: Vec<f32> = (0..64).map(|_| next()).collect();
+        let mut positions = original.clone();
+        let mut velocities: Vec<f32> = original.iter().map(|p| -p).collect();
+        let mut indices: Vec<u32> = (0..32).collect();
+
+        morton_sort(&mut positions, &mut velocities, &mut indices, 100.0, 100.0);
+
+        let mut sorted = indices.clone();
+        sorted.sort_unstable();
+        assert_eq!(sorted, (0..32).collect::<Vec<u32>>());
+
+        for (k, &id) in indices.iter().enumerate() {
+            let id = id as usize;
+            assert_eq!(positions[k * 2], original[id * 2]);
+            assert_eq!(velocities[k * 2 + 1], -original[id * 2 + 1]);
+        }
+
+        let codes: Vec<u32> = (0..32)
+            .map(|k| morton_code(positions[k * 2], positions[k * 2 + 1], 100.0, 100.0))
+            .collect();
+        assert!(codes.windows(2).all(|w| w[0] <= w[1]));
+    }
+
+    #[test]
+    fn test_morton_sort_short_arrays_stay_consistent() {
+        // Three boids in reverse Morton order, but velocities for only two
+        let mut positions = vec![90.0, 90.0, 50.0, 50.0, 10.0, 10.0];
+        let mut velocities = vec![9.0, 9.0, 5.0, 5.0];
+        let mut indices = vec![0, 1, 2];
+
+        morton_sort(&mut positions, &mut velocities, &mut indices, 100.0, 100.0);
+
+        // Only the first two are sorted, each keeping its own velocity and ID
+        assert_eq!(positions, vec![50.0, 50.0, 90.0, 90.0, 10.0, 10.0]);
+        assert_eq!(velocities, vec![5.0, 5.0, 9.0, 9.0]);
+        assert_eq!(indices, vec![1, 0, 2]);
+    }
+}
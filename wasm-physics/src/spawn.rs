@@ -0,0 +1,70 @@
+//! Deterministic initial flock layouts.
+
+use wasm_bindgen::prelude::*;
+
+use crate::rng::{next_unit, state_from_seed};
+
+/// Fill the flock with uniformly random positions and headings.
+///
+/// Positions are uniform over `[0, width) × [0, height)` and every velocity
+/// has magnitude `speed` in a uniformly random direction. The same `seed`
+/// always produces the same layout, on any machine.
+///
+/// # Arguments
+/// * `positions` - Mutable array of positions (x, y pairs)
+/// * `velocities` - Mutable array of velocities (x, y pairs)
+/// * `width` - World width
+/// * `height` - World height
+/// * `speed` - Initial speed of every boid
+/// * `seed` - Generator seed
+#[wasm_bindgen]
+pub fn spawn_random(
+    positions: &mut [f32],
+    velocities: &mut [f32],
+    width: f32,
+    height: f32,
+    speed: f32,
+    seed: u64,
+) {
+    let mut state = state_from_seed(seed);
+
+    for p in positions.chunks_exact_mut(2) {
+        p[0] = next_unit(&mut state) * width;
+        p[1] = next_unit(&mut state) * height;
+    }
+
+    for v in velocities.chunks_exact_mut(2) {
+        let angle = next_unit(&mut state) * core::f32::consts::TAU;
+        v[0] = angle.cos() * speed;
+        v[1] = angle.sin() * speed;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spawn_random_reproducible() {
+        let spawn = |seed| {
+            let mut positions = vec![0.0f32; 40];
+            let mut velocities = vec![0.0f32; 40];
+            spawn_random(&mut positions, &mut velocities, 800.0, 600.0, 2.0, seed);
+            (positions, velocities)
+        };
+
+        let (pa, va) = spawn(42);
+        let (pb, vb) = spawn(42);
+        let (pc, _) = spawn(43);
+
+        let bits = |v: &[f32]| v.iter().map(|x| x.to_bits()).collect::<Vec<_>>();
+        assert_eq!(bits(&pa), bits(&pb));
+        assert_eq!(bits(&va), bits(&vb));
+        assert_ne!(pa, pc);
+
+        for (p, v) in pa.chunks(2).zip(va.chunks(2)) {
+            assert!((0.0..800.0).contains(&p[0]) && (0.0..600.0).contains(&p[1]));
+            assert!(((v[0] * v[0] + v[1] * v[1]).sqrt() - 2.0).abs() < 1e-5);
+        }
+    }
+}
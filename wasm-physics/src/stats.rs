@@ -0,0 +1,423 @@
+//! Flock diagnostics and aggregate statistics.
+
+use wasm_bindgen::prelude::*;
+
+use crate::grid::SpatialGrid;
+use crate::read_pair;
+
+/// Batch count neighbors within a radius.
+///
+/// Writes the number of other boids within `radius` of each boid, which is
+/// handy for coloring by local density.
+///
+/// # Arguments
+/// * `positions` - Array of positions (x, y pairs)
+/// * `out_counts` - Output array of neighbor counts (one per boid)
+/// * `radius` - Neighbor radius
+#[wasm_bindgen]
+pub fn count_neighbors_all(positions: &[f32], out_counts: &mut [u32], radius: f32) {
+    let count = positions.len() / 2;
+    let radius_sq = radius * radius;
+
+    for (i, out) in out_counts.iter_mut().enumerate().take(count) {
+        let (px, py) = read_pair(positions, i);
+
+        *out = (0..count)
+            .filter(|&j| {
+                let (qx, qy) = read_pair(positions, j);
+                j != i && (px - qx) * (px - qx) + (py - qy) * (py - qy) < radius_sq
+            })
+            .count() as u32;
+    }
+}
+
+/// Total kinetic energy of the flock, `Σ ½ m |v|²`.
+///
+/// Boids without a mass entry use a mass of 1.
+///
+/// # Arguments
+/// * `velocities` - Array of velocities (x, y pairs)
+/// * `masses` - Mass of each boid
+#[wasm_bindgen]
+pub fn total_kinetic_energy(velocities: &[f32], masses: &[f32]) -> f32 {
+    (0..velocities.len() / 2)
+        .map(|i| {
+            let (vx, vy) = read_pair(velocities, i);
+            let m = masses.get(i).copied().unwrap_or(1.0);
+            0.5 * m * (vx * vx + vy * vy)
+        })
+        .sum()
+}
+
+/// Total momentum of the flock, `Σ m v`.
+///
+/// Boids without a mass entry use a mass of 1.
+///
+/// # Arguments
+/// * `velocities` - Array of velocities (x, y pairs)
+/// * `masses` - Mass of each boid
+/// * `out` - Output momentum vector (x, y), at least 2 elements
+#[wasm_bindgen]
+pub fn total_momentum(velocities: &[f32], masses: &[f32], out: &mut [f32]) {
+    let mut px = 0.0;
+    let mut py = 0.0;
+
+    for i in 0..velocities.len() / 2 {
+        let (vx, vy) = read_pair(velocities, i);
+        let m = masses.get(i).copied().unwrap_or(1.0);
+        px += m * vx;
+        py += m * vy;
+    }
+
+    if let [ox, oy, ..] = out {
+        *ox = px;
+        *oy = py;
+    }
+}
+
+/// Average position of the flock.
+///
+/// Writes zeros for an empty flock.
+///
+/// # Arguments
+/// * `positions` - Array of positions (x, y pairs)
+/// * `out` - Output centroid (x, y), at least 2 elements
+#[wasm_bindgen]
+pub fn flock_centroid(positions: &[f32], out: &mut [f32]) {
+    let count = positions.len() / 2;
+    let mut sum_x = 0.0;
+    let mut sum_y = 0.0;
+
+    for i in 0..count {
+        let (px, py) = read_pair(positions, i);
+        sum_x += px;
+        sum_y += py;
+    }
+
+    let (cx, cy) = if count > 0 {
+        (sum_x / count as f32, sum_y / count as f32)
+    } else {
+        (0.0, 0.0)
+    };
+
+    if let [ox, oy, ..] = out {
+        *ox = cx;
+        *oy = cy;
+    }
+}
+
+/// Axis-aligned bounding box of the flock.
+///
+/// When compiled with `simd128`, two boids are reduced per `f32x4_min` /
+/// `f32x4_max` and any trailing boid goes through the scalar loop; min and
+/// max are exact, so both paths agree bit for bit on finite input. Writes
+/// zeros for an empty flock.
+///
+/// # Arguments
+/// * `positions` - Array of positions (x, y pairs)
+/// * `out` - Output bounds (min_x, min_y, max_x, max_y), at least 4 elements
+#[wasm_bindgen]
+pub fn flock_bounds(positions: &[f32], out: &mut [f32]) {
+    let mut bounds = [f32::MAX, f32::MAX, f32::MIN, f32::MIN];
+
+    #[cfg(target_feature = "simd128")]
+    let start = if crate::simd_enabled() {
+        bounds_simd(positions, &mut bounds)
+    } else {
+        0
+    };
+    #[cfg(not(target_feature = "simd128"))]
+    let start = 0;
+
+    bounds_scalar(&positions[start * 2..], &mut bounds);
+
+    if positions.len() < 2 {
+        bounds = [0.0; 4];
+    }
+
+    for (o, b) in out.iter_mut().zip(bounds) {
+        *o = b;
+    }
+}
+
+/// Grow `bounds` (min_x, min_y, max_x, max_y) to cover every pair.
+fn bounds_scalar(positions: &[f32], bounds: &mut [f32; 4]) {
+    for p in positions.chunks_exact(2) {
+        bounds[0] = bounds[0].min(p[0]);
+        bounds[1] = bounds[1].min(p[1]);
+        bounds[2] = bounds[2].max(p[0]);
+        bounds[3] = bounds[3].max(p[1]);
+    }
+}
+
+/// SIMD reduction for [`flock_bounds`], two boids per `v128`.
+///
+/// Returns the first boid left for the scalar loop.
+#[cfg(target_feature = "simd128")]
+fn bounds_simd(positions: &[f32], bounds: &mut [f32; 4]) -> usize {
+    use core::arch::wasm32::*;
+
+    let chunks = positions.len() / 4;
+    if chunks == 0 {
+        return 0;
+    }
+
+    let mut lo = f32x4_splat(f32::MAX);
+    let mut hi = f32x4_splat(f32::MIN);
+    for c in 0..chunks {
+        // SAFETY: `c * 4 + 4 <= positions.len()`; unaligned access is fine.
+        let v = unsafe { v128_load(positions.as_ptr().add(c * 4) as *const v128) };
+        lo = f32x4_min(lo, v);
+        hi = f32x4_max(hi, v);
+    }
+
+    // Lanes are (x, y, x, y): fold the upper pair onto the lower
+    let lo = f32x4_min(lo, i32x4_shuffle::<2, 3, 0, 1>(lo, lo));
+    let hi = f32x4_max(hi, i32x4_shuffle::<2, 3, 0, 1>(hi, hi));
+    bounds[0] = bounds[0].min(f32x4_extract_lane::<0>(lo));
+    bounds[1] = bounds[1].min(f32x4_extract_lane::<1>(lo));
+    bounds[2] = bounds[2].max(f32x4_extract_lane::<0>(hi));
+    bounds[3] = bounds[3].max(f32x4_extract_lane::<1>(hi));
+
+    chunks * 2
+}
+
+/// Average position of the flock on a toroidal world.
+///
+/// Each coordinate is mapped to an angle around its wrapped axis, the unit
+/// vectors are averaged (circular mean) and the mean angle is mapped back,
+/// so a flock straddling a seam gets a centroid on the seam rather than in
+/// the middle of the world. Results lie in `[0, width) × [0, height)`.
+/// Writes zeros for an empty flock.
+///
+/// # Arguments
+/// * `positions` - Array of positions (x, y pairs)
+/// * `width` - World width
+/// * `height` - World height
+/// * `out` - Output centroid (x, y), at least 2 elements
+#[wasm_bindgen]
+pub fn flock_centroid_wrapped(positions: &[f32], width: f32, height: f32, out: &mut [f32]) {
+    use core::f32::consts::TAU;
+
+    let mut sums = [(0.0f32, 0.0f32); 2];
+    let sizes = [width, height];
+
+    for p in positions.chunks_exact(2) {
+        for k in 0..2 {
+            let (sin, cos) = (p[k] / sizes[k] * TAU).sin_cos();
+            sums[k].0 += sin;
+            sums[k].1 += cos;
+        }
+    }
+
+    let mut centroid = [0.0; 2];
+    if positions.len() >= 2 {
+        for k in 0..2 {
+            let angle = sums[k].0.atan2(sums[k].1).rem_euclid(TAU);
+            centroid[k] = (angle / TAU * sizes[k]) % sizes[k];
+        }
+    }
+
+    for (o, c) in out.iter_mut().zip(centroid) {
+        *o = c;
+    }
+}
+
+/// Vicsek order parameter, `|Σ v̂| / N`.
+///
+/// 1 when every boid heads the same way, near 0 for random or opposed
+/// headings. Stationary boids have no heading and contribute nothing to the
+/// sum, but still count toward `N`. Returns 0 for an empty flock.
+///
+/// # Arguments
+/// * `velocities` - Array of velocities (x, y pairs)
+#[wasm_bindgen]
+pub fn order_parameter(velocities: &[f32]) -> f32 {
+    let count = velocities.len() / 2;
+    if count == 0 {
+        return 0.0;
+    }
+
+    let mut sx = 0.0;
+    let mut sy = 0.0;
+    for i in 0..count {
+        let (vx, vy) = read_pair(velocities, i);
+        let speed = (vx * vx + vy * vy).sqrt();
+        if speed > 0.0 {
+            sx += vx / speed;
+            sy += vy / speed;
+        }
+    }
+
+    (sx * sx + sy * sy).sqrt() / count as f32
+}
+
+/// Find the first boid with a NaN or infinite position or velocity.
+///
+/// Returns the boid's index, or -1 when every value is finite. Checking
+/// after each step pinpoints the step and boid where a bad force config
+/// first contaminated the flock.
+///
+/// # Arguments
+/// * `positions` - Array of positions (x, y pairs)
+/// * `velocities` - Array of velocities (x, y pairs)
+#[wasm_bindgen]
+pub fn validate_finite(positions: &[f32], velocities: &[f32]) -> i32 {
+    let first_bad = |values: &[f32]| values.iter().position(|v| !v.is_finite()).map(|k| k / 2);
+
+    match (first_bad(positions), first_bad(velocities)) {
+        (Some(p), Some(v)) => p.min(v) as i32,
+        (Some(i), None) | (None, Some(i)) => i as i32,
+        (None, None) => -1,
+    }
+}
+
+/// Mean distance from each boid to its single closest neighbor.
+///
+/// Uses [`SpatialGrid::k_nearest`], so `grid` must have been rebuilt from
+/// `positions`. Returns 0 when no boid has a neighbor.
+///
+/// # Arguments
+/// * `positions` - Array of positions (x, y pairs)
+/// * `grid` - Spatial grid over `positions`
+#[wasm_bindgen]
+pub fn mean_nearest_neighbor_distance(positions: &[f32], grid: &SpatialGrid) -> f32 {
+    let mut nearest = [0u32; 1];
+    let mut total = 0.0;
+    let mut counted = 0u32;
+
+    for i in 0..positions.len() / 2 {
+        if grid.k_nearest(positions, i, 1, &mut nearest) == 1 {
+            let (px, py) = read_pair(positions, i);
+            let (qx, qy) = read_pair(positions, nearest[0] as usize);
+            total += ((px - qx) * (px - qx) + (py - qy) * (py - qy)).sqrt();
+            counted += 1;
+        }
+    }
+
+    if counted > 0 {
+        total / counted as f32
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_count_neighbors() {
+        let positions = vec![0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 1.0, 1.0, 50.0, 50.0];
+        let mut counts = vec![99; 5];
+
+        count_neighbors_all(&positions, &mut counts, 2.0);
+
+        assert_eq!(counts, vec![3, 3, 3, 3, 0]);
+    }
+
+    #[test]
+    fn test_opposing_boids_energy_and_momentum() {
+        let velocities = vec![3.0, 0.0, -1.5, 0.0];
+        let masses = vec![1.0, 2.0];
+        let mut momentum = vec![9.0; 2];
+
+        total_momentum(&velocities, &masses, &mut momentum);
+        let energy = total_kinetic_energy(&velocities, &masses);
+
+        assert_eq!(momentum, vec![0.0, 0.0]);
+        // ½·1·9 + ½·2·2.25
+        assert!((energy - 6.75).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_centroid_and_bounds() {
+        let positions = vec![0.0, 10.0, 4.0, -2.0, 2.0, 4.0];
+        let mut centroid = vec![0.0; 2];
+        let mut bounds = vec![0.0; 4];
+
+        flock_centroid(&positions, &mut centroid);
+        flock_bounds(&positions, &mut bounds);
+
+        assert_eq!(centroid, vec![2.0, 4.0]);
+        assert_eq!(bounds, vec![0.0, -2.0, 4.0, 10.0]);
+    }
+
+    #[test]
+    fn test_centroid_and_bounds_empty() {
+        let mut centroid = vec![7.0; 2];
+        let mut bounds = vec![7.0; 4];
+
+        flock_centroid(&[], &mut centroid);
+        flock_bounds(&[], &mut bounds);
+
+        assert_eq!(centroid, vec![0.0; 2]);
+        assert_eq!(bounds, vec![0.0; 4]);
+    }
+
+    #[test]
+    fn test_centroid_wrapped_across_seam() {
+        let positions = vec![1.0, 40.0, 99.0, 60.0];
+        let mut centroid = vec![7.0; 2];
+
+        flock_centroid_wrapped(&positions, 100.0, 100.0, &mut centroid);
+
+        // On the x seam, not at 50
+        assert!(centroid[0] < 0.01 || centroid[0] > 99.99);
+        assert!((centroid[1] - 50.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_bounds_matches_scalar() {
+        let positions: Vec<f32> = (0..2002)
+            .map(|i| ((i as f32 * 12.9898).sin() * 43758.545).fract() * 1000.0 - 300.0)
+            .collect();
+        let mut bounds = vec![0.0; 4];
+        let mut scalar = [f32::MAX, f32::MAX, f32::MIN, f32::MIN];
+
+        flock_bounds(&positions, &mut bounds);
+        bounds_scalar(&positions, &mut scalar);
+
+        assert_eq!(bounds, scalar);
+    }
+
+    #[test]
+    fn test_order_parameter() {
+        let aligned = vec![1.0, 1.0, 3.0, 3.0, 0.5, 0.5];
+        let opposed = vec![2.0, 0.0, -5.0, 0.0, 0.0, 1.0, 0.0, -3.0];
+
+        assert!((order_parameter(&aligned) - 1.0).abs() < 1e-6);
+        assert!(order_parameter(&opposed).abs() < 1e-6);
+        assert_eq!(order_parameter(&[]), 0.0);
+    }
+
+    #[test]
+    fn test_validate_finite() {
+        let positions = vec![1.0; 10];
+        let mut velocities = vec![0.5; 10];
+
+        assert_eq!(validate_finite(&positions, &velocities), -1);
+
+        velocities[7] = f32::NAN;
+        assert_eq!(validate_finite(&positions, &velocities), 3);
+
+        let mut positions = positions;
+        positions[2] = f32::INFINITY;
+        assert_eq!(validate_finite(&positions, &velocities), 1);
+    }
+
+    #[test]
+    fn test_mean_nearest_neighbor_distance() {
+        // 6 x 5 lattice with spacing 7
+        let positions: Vec<f32> = (0..30)
+            .flat_map(|i| [5.0 + (i % 6) as f32 * 7.0, 5.0 + (i / 6) as f32 * 7.0])
+            .collect();
+        let mut grid = SpatialGrid::new(10.0);
+        grid.rebuild(&positions, 50.0, 50.0);
+
+        assert!((mean_nearest_neighbor_distance(&positions, &grid) - 7.0).abs() < 1e-5);
+
+        grid.rebuild(&positions[..2], 50.0, 50.0);
+        assert_eq!(mean_nearest_neighbor_distance(&positions[..2], &grid), 0.0);
+    }
+}
@@ -0,0 +1,800 @@
+//! Reynolds-style steering behaviors toward or away from points.
+//!
+//! Steering is `desired_velocity - velocity`, clamped to `max_force`, and is
+//! added into `out_accel`. Behaviors without a `max_speed` argument use the
+//! boid's current speed as the desired speed (but at least `max_force`, so
+//! stationary boids still get moving) and leave speed limits to the
+//! integrator.
+
+use wasm_bindgen::prelude::*;
+
+use crate::rng::next_unit;
+use crate::{add_pair, clamp_magnitude, read_pair};
+
+/// Batch steer boids toward a point.
+///
+/// # Arguments
+/// * `positions` - Array of positions (x, y pairs)
+/// * `velocities` - Array of velocities (x, y pairs)
+/// * `out_accel` - Mutable array of accelerations to add into (x, y pairs)
+/// * `target_x` - Target X coordinate
+/// * `target_y` - Target Y coordinate
+/// * `max_force` - Maximum steering force magnitude
+#[wasm_bindgen]
+pub fn seek_point_all(
+    positions: &[f32],
+    velocities: &[f32],
+    out_accel: &mut [f32],
+    target_x: f32,
+    target_y: f32,
+    max_force: f32,
+) {
+    for i in 0..positions.len() / 2 {
+        let (px, py) = read_pair(positions, i);
+        let (vx, vy) = read_pair(velocities, i);
+        let (fx, fy) = steer_along(target_x - px, target_y - py, vx, vy, max_force);
+        add_pair(out_accel, i, fx, fy);
+    }
+}
+
+/// Batch steer boids away from a point.
+///
+/// # Arguments
+/// * `positions` - Array of positions (x, y pairs)
+/// * `velocities` - Array of velocities (x, y pairs)
+/// * `out_accel` - Mutable array of accelerations to add into (x, y pairs)
+/// * `target_x` - Threat X coordinate
+/// * `target_y` - Threat Y coordinate
+/// * `radius` - Only boids closer than this flee; `<= 0` means no limit
+/// * `max_force` - Maximum steering force magnitude
+#[wasm_bindgen]
+pub fn flee_point_all(
+    positions: &[f32],
+    velocities: &[f32],
+    out_accel: &mut [f32],
+    target_x: f32,
+    target_y: f32,
+    radius: f32,
+    max_force: f32,
+) {
+    let radius_sq = radius * radius;
+
+    for i in 0..positions.len() / 2 {
+        let (px, py) = read_pair(positions, i);
+        let dx = px - target_x;
+        let dy = py - target_y;
+
+        if radius > 0.0 && dx * dx + dy * dy >= radius_sq {
+            continue;
+        }
+
+        let (vx, vy) = read_pair(velocities, i);
+        let (fx, fy) = steer_along(dx, dy, vx, vy, max_force);
+        add_pair(out_accel, i, fx, fy);
+    }
+}
+
+/// Batch steer each boid toward its own target.
+///
+/// `targets` holds one (x, y) goal per boid; boids beyond the end of the
+/// array receive no force.
+///
+/// # Arguments
+/// * `positions` - Array of positions (x, y pairs)
+/// * `velocities` - Array of velocities (x, y pairs)
+/// * `out_accel` - Mutable array of accelerations to add into (x, y pairs)
+/// * `targets` - Per-boid target positions (x, y pairs)
+/// * `max_force` - Maximum steering force magnitude
+#[wasm_bindgen]
+pub fn seek_targets_all(
+    positions: &[f32],
+    velocities: &[f32],
+    out_accel: &mut [f32],
+    targets: &[f32],
+    max_force: f32,
+) {
+    let count = (positions.len() / 2).min(targets.len() / 2);
+
+    for i in 0..count {
+        let (px, py) = read_pair(positions, i);
+        let (tx, ty) = read_pair(targets, i);
+        let (vx, vy) = read_pair(velocities, i);
+        let (fx, fy) = steer_along(tx - px, ty - py, vx, vy, max_force);
+        add_pair(out_accel, i, fx, fy);
+    }
+}
+
+/// Batch steer boids to arrive at a point, slowing down on approach.
+///
+/// Outside `slowing_radius` the desired speed is `max_speed`; inside it
+/// ramps linearly to zero at the target, so boids settle instead of
+/// overshooting and oscillating.
+///
+/// # Arguments
+/// * `positions` - Array of positions (x, y pairs)
+/// * `velocities` - Array of velocities (x, y pairs)
+/// * `out_accel` - Mutable array of accelerations to add into (x, y pairs)
+/// * `target_x` - Target X coordinate
+/// * `target_y` - Target Y coordinate
+/// * `slowing_radius` - Distance at which boids start slowing
+/// * `max_speed` - Desired speed outside the slowing radius
+/// * `max_force` - Maximum steering force magnitude
+#[wasm_bindgen]
+#[allow(clippy::too_many_arguments)]
+pub fn arrive_point_all(
+    positions: &[f32],
+    velocities: &[f32],
+    out_accel: &mut [f32],
+    target_x: f32,
+    target_y: f32,
+    slowing_radius: f32,
+    max_speed: f32,
+    max_force: f32,
+) {
+    for i in 0..positions.len() / 2 {
+        let (px, py) = read_pair(positions, i);
+        let (vx, vy) = read_pair(velocities, i);
+        let (fx, fy) = arrive_steering(
+            target_x - px,
+            target_y - py,
+            vx,
+            vy,
+            slowing_radius,
+            max_speed,
+            max_force,
+        );
+        add_pair(out_accel, i, fx, fy);
+    }
+}
+
+/// Batch steer prey away from predators.
+///
+/// Each boid within `panic_radius` of a predator steers away from the
+/// nearest one, with a force limit that grows from zero at the edge of the
+/// radius to `strength` at the predator itself. Boids outside every panic
+/// radius are unaffected.
+///
+/// # Arguments
+/// * `positions` - Array of positions (x, y pairs)
+/// * `velocities` - Array of velocities (x, y pairs)
+/// * `out_accel` - Mutable array of accelerations to add into (x, y pairs)
+/// * `predators` - Array of predator positions (x, y pairs)
+/// * `panic_radius` - Distance at which prey start fleeing
+/// * `strength` - Maximum flee force magnitude
+#[wasm_bindgen]
+pub fn flee_predators_all(
+    positions: &[f32],
+    velocities: &[f32],
+    out_accel: &mut [f32],
+    predators: &[f32],
+    panic_radius: f32,
+    strength: f32,
+) {
+    let radius_sq = panic_radius * panic_radius;
+
+    for i in 0..positions.len() / 2 {
+        let (px, py) = read_pair(positions, i);
+        let mut nearest: Option<(f32, f32, f32)> = None;
+
+        for j in 0..predators.len() / 2 {
+            let (qx, qy) = read_pair(predators, j);
+            let dx = px - qx;
+            let dy = py - qy;
+            let dist_sq = dx * dx + dy * dy;
+
+            if dist_sq < radius_sq && nearest.is_none_or(|(_, _, d)| dist_sq < d) {
+                nearest = Some((dx, dy, dist_sq));
+            }
+        }
+
+        let Some((dx, dy, dist_sq)) = nearest else {
+            continue;
+        };
+
+        let depth = 1.0 - dist_sq.sqrt() / panic_radius;
+        let (vx, vy) = read_pair(velocities, i);
+        let (fx, fy) = steer_along(dx, dy, vx, vy, strength * depth);
+        add_pair(out_accel, i, fx, fy);
+    }
+}
+
+/// Batch steer boids toward where a moving target will be.
+///
+/// Each boid aims at the target's position extrapolated by the time it would
+/// take to cover the current distance at its own speed, so distant boids lead
+/// the target further than close ones.
+///
+/// # Arguments
+/// * `positions` - Array of positions (x, y pairs)
+/// * `velocities` - Array of velocities (x, y pairs)
+/// * `out_accel` - Mutable array of accelerations to add into (x, y pairs)
+/// * `target_pos_x` - Target X coordinate
+/// * `target_pos_y` - Target Y coordinate
+/// * `target_vel_x` - Target X velocity
+/// * `target_vel_y` - Target Y velocity
+/// * `max_force` - Maximum steering force magnitude
+#[wasm_bindgen]
+#[allow(clippy::too_many_arguments)]
+pub fn pursue_all(
+    positions: &[f32],
+    velocities: &[f32],
+    out_accel: &mut [f32],
+    target_pos_x: f32,
+    target_pos_y: f32,
+    target_vel_x: f32,
+    target_vel_y: f32,
+    max_force: f32,
+) {
+    for i in 0..positions.len() / 2 {
+        let (px, py) = read_pair(positions, i);
+        let (vx, vy) = read_pair(velocities, i);
+        let (tx, ty) = predict_target(
+            px,
+            py,
+            vx,
+            vy,
+            (target_pos_x, target_pos_y, target_vel_x, target_vel_y),
+            max_force,
+        );
+        let (fx, fy) = steer_along(tx - px, ty - py, vx, vy, max_force);
+        add_pair(out_accel, i, fx, fy);
+    }
+}
+
+/// Batch steer boids away from where a moving threat will be.
+///
+/// The counterpart of [`pursue_all`], fleeing the predicted position.
+///
+/// # Arguments
+/// * `positions` - Array of positions (x, y pairs)
+/// * `velocities` - Array of velocities (x, y pairs)
+/// * `out_accel` - Mutable array of accelerations to add into (x, y pairs)
+/// * `target_pos_x` - Threat X coordinate
+/// * `target_pos_y` - Threat Y coordinate
+/// * `target_vel_x` - Threat X velocity
+/// * `target_vel_y` - Threat Y velocity
+/// * `max_force` - Maximum steering force magnitude
+#[wasm_bindgen]
+#[allow(clippy::too_many_arguments)]
+pub fn evade_all(
+    positions: &[f32],
+    velocities: &[f32],
+    out_accel: &mut [f32],
+    target_pos_x: f32,
+    target_pos_y: f32,
+    target_vel_x: f32,
+    target_vel_y: f32,
+    max_force: f32,
+) {
+    for i in 0..positions.len() / 2 {
+        let (px, py) = read_pair(positions, i);
+        let (vx, vy) = read_pair(velocities, i);
+        let (tx, ty) = predict_target(
+            px,
+            py,
+            vx,
+            vy,
+            (target_pos_x, target_pos_y, target_vel_x, target_vel_y),
+            max_force,
+        );
+        let (fx, fy) = steer_along(px - tx, py - ty, vx, vy, max_force);
+        add_pair(out_accel, i, fx, fy);
+    }
+}
+
+/// Batch steer boids to trail behind a leader boid.
+///
+/// Every boid except `leader_idx` arrives at the point `follow_distance`
+/// behind the leader along its heading (at the leader itself when it is at
+/// rest), slowing within `follow_distance` of that point. Followers closer
+/// than `follow_distance` to each other are also pushed apart, so they fan
+/// out instead of piling onto the same spot. The combined force is limited
+/// to `max_force`; the leader gets none, and an out-of-range `leader_idx`
+/// does nothing.
+///
+/// # Arguments
+/// * `positions` - Array of positions (x, y pairs)
+/// * `velocities` - Array of velocities (x, y pairs)
+/// * `out_accel` - Mutable array of accelerations to add into (x, y pairs)
+/// * `leader_idx` - Index of the leader boid
+/// * `follow_distance` - Distance to keep behind the leader
+/// * `max_force` - Maximum steering force magnitude
+#[wasm_bindgen]
+pub fn follow_leader_all(
+    positions: &[f32],
+    velocities: &[f32],
+    out_accel: &mut [f32],
+    leader_idx: usize,
+    follow_distance: f32,
+    max_force: f32,
+) {
+    let count = positions.len() / 2;
+    if leader_idx >= count {
+        return;
+    }
+
+    let (lx, ly) = read_pair(positions, leader_idx);
+    let (lvx, lvy) = read_pair(velocities, leader_idx);
+    let leader_speed = (lvx * lvx + lvy * lvy).sqrt();
+    let (behind_x, behind_y) = if leader_speed > 0.0 {
+        let scale = follow_distance / leader_speed;
+        (lx - lvx * scale, ly - lvy * scale)
+    } else {
+        (lx, ly)
+    };
+    let spacing_sq = follow_distance * follow_distance;
+
+    for i in (0..count).filter(|&i| i != leader_idx) {
+        let (px, py) = read_pair(positions, i);
+        let (vx, vy) = read_pair(velocities, i);
+        let speed = (vx * vx + vy * vy).sqrt().max(leader_speed).max(max_force);
+        let (mut fx, mut fy) = arrive_steering(
+            behind_x - px,
+            behind_y - py,
+            vx,
+            vy,
+            follow_distance,
+            speed,
+            max_force,
+        );
+
+        for j in (0..count).filter(|&j| j != i && j != leader_idx) {
+            let (qx, qy) = read_pair(positions, j);
+            let dx = px - qx;
+            let dy = py - qy;
+            let dist_sq = dx * dx + dy * dy;
+
+            if dist_sq > 0.0 && dist_sq < spacing_sq {
+                let dist = dist_sq.sqrt();
+                let push = max_force * (1.0 - dist / follow_distance) / dist;
+                fx += dx * push;
+                fy += dy * push;
+            }
+        }
+
+        let (fx, fy) = clamp_magnitude(fx, fy, max_force);
+        add_pair(out_accel, i, fx, fy);
+    }
+}
+
+/// Batch steer boids to follow a polyline path.
+///
+/// Each boid is projected onto the nearest path segment. Boids farther than
+/// `path_radius` from the path steer back toward the projected point, pushed
+/// `path_radius` further along the segment so they rejoin moving forward.
+///
+/// # Arguments
+/// * `positions` - Array of positions (x, y pairs)
+/// * `velocities` - Array of velocities (x, y pairs)
+/// * `out_accel` - Mutable array of accelerations to add into (x, y pairs)
+/// * `path` - Waypoints of the path in order (x, y pairs)
+/// * `path_radius` - Half-width of the path corridor
+/// * `max_force` - Maximum steering force magnitude
+#[wasm_bindgen]
+pub fn follow_path_all(
+    positions: &[f32],
+    velocities: &[f32],
+    out_accel: &mut [f32],
+    path: &[f32],
+    path_radius: f32,
+    max_force: f32,
+) {
+    let waypoints = path.len() / 2;
+    if waypoints == 0 {
+        return;
+    }
+    let radius_sq = path_radius * path_radius;
+
+    for i in 0..positions.len() / 2 {
+        let (px, py) = read_pair(positions, i);
+
+        // Nearest projection over all segments (a lone waypoint is a
+        // zero-length segment)
+        let mut best = (f32::MAX, 0.0, 0.0, 0.0, 0.0);
+        for s in 0..waypoints.saturating_sub(1).max(1) {
+            let (ax, ay) = read_pair(path, s);
+            let (bx, by) = read_pair(path, (s + 1).min(waypoints - 1));
+            let sx = bx - ax;
+            let sy = by - ay;
+            let seg_len_sq = sx * sx + sy * sy;
+
+            let (qx, qy, dir_x, dir_y) = if seg_len_sq > 0.0 {
+                let t = (((px - ax) * sx + (py - ay) * sy) / seg_len_sq).clamp(0.0, 1.0);
+                let seg_len = seg_len_sq.sqrt();
+                (ax + sx * t, ay + sy * t, sx / seg_len, sy / seg_len)
+            } else {
+                (ax, ay, 0.0, 0.0)
+            };
+
+            let dist_sq = (px - qx) * (px - qx) + (py - qy) * (py - qy);
+            if dist_sq < best.0 {
+                best = (dist_sq, qx, qy, dir_x, dir_y);
+            }
+        }
+
+        let (dist_sq, qx, qy, dir_x, dir_y) = best;
+        if dist_sq <= radius_sq {
+            continue;
+        }
+
+        let target_x = qx + dir_x * path_radius;
+        let target_y = qy + dir_y * path_radius;
+        let (vx, vy) = read_pair(velocities, i);
+        let (fx, fy) = steer_along(target_x - px, target_y - py, vx, vy, max_force);
+        add_pair(out_accel, i, fx, fy);
+    }
+}
+
+/// Batch apply Reynolds wander.
+///
+/// Each boid keeps a wander angle in `wander_angle`, which drifts by a
+/// random amount of at most `jitter` radians every frame. The wander point
+/// sits on a circle of `circle_radius` centered `circle_distance` ahead of
+/// the boid, at that angle from its heading, and the boid is pushed toward
+/// it with magnitude `wander_strength`. Because the angle accumulates, the
+/// path is a smooth random walk rather than per-frame noise.
+///
+/// `rng_state` holds one xorshift state per boid; the same seeds and angles
+/// always produce the same forces. Boids at rest wander relative to +x.
+///
+/// # Arguments
+/// * `velocities` - Array of velocities (x, y pairs)
+/// * `out_accel` - Mutable array of accelerations to add into (x, y pairs)
+/// * `rng_state` - Mutable per-boid PRNG state
+/// * `wander_angle` - Mutable per-boid wander angle, in radians
+/// * `wander_strength` - Wander force magnitude
+/// * `jitter` - Maximum angular perturbation per frame, in radians
+/// * `circle_distance` - Distance of the wander circle ahead of the boid
+/// * `circle_radius` - Radius of the wander circle
+#[wasm_bindgen]
+#[allow(clippy::too_many_arguments)]
+pub fn wander_all(
+    velocities: &[f32],
+    out_accel: &mut [f32],
+    rng_state: &mut [u32],
+    wander_angle: &mut [f32],
+    wander_strength: f32,
+    jitter: f32,
+    circle_distance: f32,
+    circle_radius: f32,
+) {
+    let states = rng_state.iter_mut().zip(wander_angle.iter_mut());
+
+    for (i, (state, angle)) in states.enumerate().take(velocities.len() / 2) {
+        let (vx, vy) = read_pair(velocities, i);
+        let heading = if vx == 0.0 && vy == 0.0 {
+            0.0
+        } else {
+            vy.atan2(vx)
+        };
+
+        *angle += (next_unit(state) * 2.0 - 1.0) * jitter;
+
+        let target_x = heading.cos() * circle_distance + (heading + *angle).cos() * circle_radius;
+        let target_y = heading.sin() * circle_distance + (heading + *angle).sin() * circle_radius;
+        let len = (target_x * target_x + target_y * target_y).sqrt();
+        if len > 0.0 {
+            let scale = wander_strength / len;
+            add_pair(out_accel, i, target_x * scale, target_y * scale);
+        }
+    }
+}
+
+/// Batch steer boids toward precomputed desired velocities.
+///
+/// Adds `clamp(desired - velocity, max_force)` per boid, so JS can compose
+/// custom behaviors by supplying only the desired velocities. Processes as
+/// many boids as both arrays hold.
+///
+/// # Arguments
+/// * `velocities` - Array of velocities (x, y pairs)
+/// * `desired` - Desired velocities (x, y pairs)
+/// * `out_accel` - Mutable array of accelerations to add into (x, y pairs)
+/// * `max_force` - Maximum steering force
+#[wasm_bindgen]
+pub fn steer_toward(velocities: &[f32], desired: &[f32], out_accel: &mut [f32], max_force: f32) {
+    for i in 0..velocities.len().min(desired.len()) / 2 {
+        let (vx, vy) = read_pair(velocities, i);
+        let (dx, dy) = read_pair(desired, i);
+        let (fx, fy) = clamp_magnitude(dx - vx, dy - vy, max_force);
+        add_pair(out_accel, i, fx, fy);
+    }
+}
+
+/// Steering that turns velocity (vx, vy) toward direction (dx, dy).
+///
+/// A zero direction yields no steering.
+pub(crate) fn steer_along(dx: f32, dy: f32, vx: f32, vy: f32, max_force: f32) -> (f32, f32) {
+    let len = (dx * dx + dy * dy).sqrt();
+    if len <= 0.0 {
+        return (0.0, 0.0);
+    }
+
+    let speed = (vx * vx + vy * vy).sqrt().max(max_force);
+    let desired_x = dx / len * speed;
+    let desired_y = dy / len * speed;
+    clamp_magnitude(desired_x - vx, desired_y - vy, max_force)
+}
+
+/// Position of a target `(x, y, vx, vy)` after the time a boid at (px, py)
+/// moving at its current speed (but at least `max_force`) needs to reach it.
+fn predict_target(
+    px: f32,
+    py: f32,
+    vx: f32,
+    vy: f32,
+    (tx, ty, tvx, tvy): (f32, f32, f32, f32),
+    max_force: f32,
+) -> (f32, f32) {
+    let dist = ((tx - px) * (tx - px) + (ty - py) * (ty - py)).sqrt();
+    let speed = (vx * vx + vy * vy).sqrt().max(max_force);
+    let t = if speed > 0.0 { dist / speed } else { 0.0 };
+    (tx + tvx * t, ty + tvy * t)
+}
+
+/// Arrival steering for a boid at offset (dx, dy) from its target.
+///
+/// At (near) zero distance the desired velocity is zero, so the boid brakes.
+pub(crate) fn arrive_steering(
+    dx: f32,
+    dy: f32,
+    vx: f32,
+    vy: f32,
+    slowing_radius: f32,
+    max_speed: f32,
+    max_force: f32,
+) -> (f32, f32) {
+    let dist = (dx * dx + dy * dy).sqrt();
+
+    let (desired_x, desired_y) = if dist < 1e-6 {
+        (0.0, 0.0)
+    } else {
+        let speed = if dist < slowing_radius {
+            max_speed * dist / slowing_radius
+        } else {
+            max_speed
+        };
+        (dx / dist * speed, dy / dist * speed)
+    };
+
+    clamp_magnitude(desired_x - vx, desired_y - vy, max_force)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seek_and_flee_direction() {
+        let positions = vec![0.0, 0.0];
+        let velocities = vec![0.0, 1.0];
+
+        let mut accel = vec![0.0; 2];
+        seek_point_all(&positions, &velocities, &mut accel, 10.0, 0.0, 0.5);
+        assert!(accel[0] > 0.0);
+        assert!((accel[0] * accel[0] + accel[1] * accel[1]).sqrt() <= 0.5 + 1e-6);
+
+        let mut accel = vec![0.0; 2];
+        flee_point_all(&positions, &velocities, &mut accel, 10.0, 0.0, 0.0, 0.5);
+        assert!(accel[0] < 0.0);
+
+        // Outside the flee radius nothing happens
+        let mut accel = vec![0.0; 2];
+        flee_point_all(&positions, &velocities, &mut accel, 10.0, 0.0, 5.0, 0.5);
+        assert_eq!(accel, vec![0.0; 2]);
+    }
+
+    #[test]
+    fn test_arrive_decelerates_inside_radius() {
+        let positions = vec![0.0, 0.0, 1.0, 0.0];
+        let velocities = vec![5.0, 0.0, 0.0, 0.0];
+        let mut accel = vec![0.0; 4];
+
+        arrive_point_all(
+            &positions,
+            &velocities,
+            &mut accel,
+            1.0,
+            0.0,
+            10.0,
+            5.0,
+            2.0,
+        );
+
+        // Racing toward a close target: brake
+        assert!((accel[0] + 2.0).abs() < 0.001);
+        // Already at the target and at rest: no NaN, no force
+        assert_eq!(accel[2..4], [0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_wander_reproducible() {
+        let velocities = vec![1.0, 0.0, 0.0, 1.0, -1.0, 0.5];
+
+        let run = |seeds: Vec<u32>| {
+            let mut state = seeds;
+            let mut angles = vec![0.0; 3];
+            let mut history = Vec::new();
+            for _ in 0..5 {
+                let mut accel = vec![0.0; 6];
+                wander_all(
+                    &velocities,
+                    &mut accel,
+                    &mut state,
+                    &mut angles,
+                    2.0,
+                    0.5,
+                    2.0,
+                    1.0,
+                );
+                history.extend(accel);
+            }
+            history
+        };
+
+        let a = run(vec![1, 2, 3]);
+        assert_eq!(a, run(vec![1, 2, 3]));
+        assert_ne!(a, run(vec![4, 5, 6]));
+
+        // Force magnitude is the wander strength
+        let len = (a[0] * a[0] + a[1] * a[1]).sqrt();
+        assert!((len - 2.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_wander_angle_persists() {
+        // Heading +x, circle 3 ahead with radius 1
+        let velocities = vec![1.0, 0.0];
+        let mut state = vec![0x1234_5678];
+        let mut angles = vec![0.0];
+        let mut history = Vec::new();
+
+        for _ in 0..50 {
+            let before = angles[0];
+            let mut accel = vec![0.0; 2];
+            wander_all(
+                &velocities,
+                &mut accel,
+                &mut state,
+                &mut angles,
+                1.0,
+                0.3,
+                3.0,
+                1.0,
+            );
+
+            // Each frame moves the stored angle by at most the jitter...
+            assert!((angles[0] - before).abs() <= 0.3);
+            // ...and the force points at the wander point for that angle
+            let (tx, ty) = (3.0 + angles[0].cos(), angles[0].sin());
+            let len = (tx * tx + ty * ty).sqrt();
+            assert!((accel[0] - tx / len).abs() < 1e-5);
+            assert!((accel[1] - ty / len).abs() < 1e-5);
+            history.push(angles[0]);
+        }
+
+        // The drift accumulates past a single frame's jitter
+        assert!(history.iter().any(|a| a.abs() > 0.3));
+    }
+
+    #[test]
+    fn test_flee_predators() {
+        let positions = vec![3.0, 4.0, 50.0, 0.0];
+        let velocities = vec![0.0; 4];
+        let mut accel = vec![0.0; 4];
+
+        flee_predators_all(&positions, &velocities, &mut accel, &[0.0, 0.0], 10.0, 2.0);
+
+        // Half-way into the panic radius: half strength, straight away
+        assert!((accel[0] - 0.6).abs() < 0.001);
+        assert!((accel[1] - 0.8).abs() < 0.001);
+        assert_eq!(accel[2..4], [0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_follow_path_returns_to_path() {
+        let path = vec![0.0, 0.0, 100.0, 0.0];
+        let positions = vec![50.0, 10.0, 50.0, 1.0];
+        let velocities = vec![1.0, 0.0, 1.0, 0.0];
+        let mut accel = vec![0.0; 4];
+
+        follow_path_all(&positions, &velocities, &mut accel, &path, 2.0, 1.0);
+
+        assert!(accel[1] < 0.0);
+        // Inside the corridor: no correction
+        assert_eq!(accel[2..4], [0.0, 0.0]);
+
+        // Degenerate path of one repeated point still pulls boids in
+        let mut accel = vec![0.0; 4];
+        follow_path_all(
+            &positions,
+            &velocities,
+            &mut accel,
+            &[50.0, 0.0, 50.0, 0.0],
+            2.0,
+            1.0,
+        );
+        assert!(accel[1] < 0.0 && accel[1].is_finite());
+    }
+
+    #[test]
+    fn test_seek_targets_per_boid() {
+        let positions = vec![0.0, 0.0, 0.0, 0.0, 0.0, 0.0];
+        let velocities = vec![0.0; 6];
+        let targets = vec![10.0, 0.0, 0.0, -10.0];
+        let mut accel = vec![0.0; 6];
+
+        seek_targets_all(&positions, &velocities, &mut accel, &targets, 1.0);
+
+        assert!((accel[0] - 1.0).abs() < 0.001 && accel[1].abs() < 0.001);
+        assert!(accel[2].abs() < 0.001 && (accel[3] + 1.0).abs() < 0.001);
+        // No target for the third boid
+        assert_eq!(accel[4..6], [0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_pursue_leads_moving_target() {
+        // Target 10 units ahead, moving up at the boid's speed
+        let positions = vec![0.0, 0.0];
+        let velocities = vec![1.0, 0.0];
+        let mut pursue = vec![0.0; 2];
+        let mut seek = vec![0.0; 2];
+        let mut evade = vec![0.0; 2];
+
+        pursue_all(
+            &positions,
+            &velocities,
+            &mut pursue,
+            10.0,
+            0.0,
+            0.0,
+            1.0,
+            5.0,
+        );
+        seek_point_all(&positions, &velocities, &mut seek, 10.0, 0.0, 5.0);
+        evade_all(
+            &positions,
+            &velocities,
+            &mut evade,
+            10.0,
+            0.0,
+            0.0,
+            1.0,
+            5.0,
+        );
+
+        // Seeking the current position has no sideways component
+        assert_eq!(seek[1], 0.0);
+        assert!(pursue[1] > 0.0);
+        assert!(evade[0] < 0.0 && evade[1] < 0.0);
+    }
+
+    #[test]
+    fn test_follow_leader() {
+        // Leader at (50, 50) heading +x; follower far below the trailing point
+        let positions = vec![50.0, 50.0, 40.0, 80.0];
+        let velocities = vec![2.0, 0.0, 0.0, 0.0];
+        let mut accel = vec![0.0; 4];
+
+        follow_leader_all(&positions, &velocities, &mut accel, 0, 10.0, 1.0);
+
+        assert_eq!(&accel[..2], &[0.0, 0.0]);
+        // Steers straight up toward (40, 50)
+        assert!(accel[2].abs() < 1e-6);
+        assert!((accel[3] + 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_steer_toward_clamped() {
+        let velocities = vec![1.0, 0.0, 0.0, 0.0];
+        let desired = vec![1.5, 0.0, 0.0, 10.0];
+        let mut accel = vec![0.0, 1.0, 0.0, 0.0];
+
+        steer_toward(&velocities, &desired, &mut accel, 2.0);
+
+        // Below the limit: the raw difference, added to what was there
+        assert_eq!(&accel[..2], &[0.5, 1.0]);
+        // Above the limit: same direction, clamped length
+        assert_eq!(&accel[2..], &[0.0, 2.0]);
+    }
+}
@@ -0,0 +1,204 @@
+//! 3D variants of the core batch functions, plus 3D-only flight dynamics.
+//!
+//! Arrays are interleaved triples: [x0, y0, z0, x1, y1, z1, ...]. Stalled
+//! boids restart in the xy plane, matching the 2D fallback heading.
+
+use wasm_bindgen::prelude::*;
+
+use crate::{fallback_heading, SPEED_EPSILON};
+
+/// 3D version of [`integrate_all`](crate::integrate_all).
+///
+/// # Arguments
+/// * `positions` - Mutable array of positions (x, y, z triples)
+/// * `velocities` - Mutable array of velocities (x, y, z triples)
+/// * `accelerations` - Array of accelerations (x, y, z triples)
+/// * `dt` - Delta time
+/// * `min_speed` - Minimum speed
+/// * `max_speed` - Maximum speed
+/// * `drag` - Drag coefficient
+#[wasm_bindgen]
+pub fn integrate_all_3d(
+    positions: &mut [f32],
+    velocities: &mut [f32],
+    accelerations: &[f32],
+    dt: f32,
+    min_speed: f32,
+    max_speed: f32,
+    drag: f32,
+) {
+    let drag_factor = 1.0 - drag;
+
+    for i in 0..positions.len() / 3 {
+        let a = read_triple(accelerations, i);
+        let v = read_triple(velocities, i);
+        let v = clamp_speed_3d(
+            i,
+            [
+                (v[0] + a[0] * dt) * drag_factor,
+                (v[1] + a[1] * dt) * drag_factor,
+                (v[2] + a[2] * dt) * drag_factor,
+            ],
+            min_speed,
+            max_speed,
+        );
+
+        for k in 0..3 {
+            if let Some(out) = velocities.get_mut(i * 3 + k) {
+                *out = v[k];
+            }
+            positions[i * 3 + k] += v[k] * dt;
+        }
+    }
+}
+
+/// 3D version of [`clamp_speeds_all`](crate::clamp_speeds_all).
+///
+/// # Arguments
+/// * `velocities` - Mutable array of velocities (x, y, z triples)
+/// * `min_speed` - Minimum speed
+/// * `max_speed` - Maximum speed
+#[wasm_bindgen]
+pub fn clamp_speeds_all_3d(velocities: &mut [f32], min_speed: f32, max_speed: f32) {
+    for (i, v) in velocities.chunks_exact_mut(3).enumerate() {
+        let clamped = clamp_speed_3d(i, [v[0], v[1], v[2]], min_speed, max_speed);
+        v.copy_from_slice(&clamped);
+    }
+}
+
+/// 3D version of [`wrap_positions_all`](crate::wrap_positions_all).
+///
+/// # Arguments
+/// * `positions` - Mutable array of positions (x, y, z triples)
+/// * `width` - World width
+/// * `height` - World height
+/// * `depth` - World depth
+#[wasm_bindgen]
+pub fn wrap_positions_all_3d(positions: &mut [f32], width: f32, height: f32, depth: f32) {
+    let sizes = [width, height, depth];
+
+    for p in positions.chunks_exact_mut(3) {
+        for (c, &size) in p.iter_mut().zip(&sizes) {
+            if *c < 0.0 {
+                *c += size;
+            } else if *c >= size {
+                *c -= size;
+            }
+        }
+    }
+}
+
+/// Batch couple hard turns to a loss of speed and altitude, like a banked
+/// turn.
+///
+/// The xy plane is horizontal and +z is up. The lateral acceleration is the
+/// horizontal part of each boid's acceleration perpendicular to its
+/// horizontal heading; with `k = bank_factor * |lateral|`, the boid loses
+/// `k` of horizontal speed (down to zero) and gains `k` of downward speed.
+/// Boids with no horizontal motion are left unchanged.
+///
+/// # Arguments
+/// * `velocities` - Mutable array of velocities (x, y, z triples)
+/// * `accelerations` - Array of accelerations (x, y, z triples)
+/// * `bank_factor` - Velocity change per unit of lateral acceleration
+#[wasm_bindgen]
+pub fn apply_banking(velocities: &mut [f32], accelerations: &[f32], bank_factor: f32) {
+    for (i, v) in velocities.chunks_exact_mut(3).enumerate() {
+        let horizontal = (v[0] * v[0] + v[1] * v[1]).sqrt();
+        if horizontal <= 0.0 {
+            continue;
+        }
+
+        let a = read_triple(accelerations, i);
+        let lateral = (a[1] * v[0] - a[0] * v[1]).abs() / horizontal;
+        let k = bank_factor * lateral;
+
+        let scale = (1.0 - k / horizontal).max(0.0);
+        v[0] *= scale;
+        v[1] *= scale;
+        v[2] -= k;
+    }
+}
+
+/// Read the (x, y, z) triple for boid `i`, treating missing lanes as zero.
+#[inline]
+pub(crate) fn read_triple(values: &[f32], i: usize) -> [f32; 3] {
+    let idx = i * 3;
+    [
+        values.get(idx).copied().unwrap_or(0.0),
+        values.get(idx + 1).copied().unwrap_or(0.0),
+        values.get(idx + 2).copied().unwrap_or(0.0),
+    ]
+}
+
+/// Clamp one 3D velocity into [min_speed, max_speed].
+fn clamp_speed_3d(i: usize, v: [f32; 3], min_speed: f32, max_speed: f32) -> [f32; 3] {
+    let speed_sq = v[0] * v[0] + v[1] * v[1] + v[2] * v[2];
+
+    if speed_sq > max_speed * max_speed {
+        let scale = max_speed / speed_sq.sqrt();
+        v.map(|c| c * scale)
+    } else if speed_sq < min_speed * min_speed {
+        if speed_sq > SPEED_EPSILON {
+            let scale = min_speed / speed_sq.sqrt();
+            v.map(|c| c * scale)
+        } else {
+            let (hx, hy) = fallback_heading(i);
+            [hx * min_speed, hy * min_speed, 0.0]
+        }
+    } else {
+        v
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_integrate_3d_z_motion() {
+        let mut positions = vec![1.0, 2.0, 3.0];
+        let mut velocities = vec![0.0, 0.0, 2.0];
+
+        integrate_all_3d(
+            &mut positions,
+            &mut velocities,
+            &[0.0; 3],
+            0.5,
+            0.0,
+            10.0,
+            0.0,
+        );
+
+        assert_eq!(positions, vec![1.0, 2.0, 4.0]);
+
+        wrap_positions_all_3d(&mut positions, 10.0, 10.0, 4.0);
+        assert_eq!(positions[2], 0.0);
+    }
+
+    #[test]
+    fn test_clamp_speeds_3d() {
+        let mut velocities = vec![2.0, 3.0, 6.0];
+
+        clamp_speeds_all_3d(&mut velocities, 0.0, 3.5);
+
+        // |(2, 3, 6)| = 7, halved
+        assert!((velocities[0] - 1.0).abs() < 1e-6);
+        assert!((velocities[2] - 3.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_banking_couples_turns() {
+        // Both fly +x at speed 4; the first turns hard (lateral 10, plus a
+        // forward push that must not count), the second only speeds up
+        let mut velocities = vec![4.0, 0.0, 1.0, 4.0, 0.0, 1.0];
+        let accelerations = vec![3.0, 10.0, 0.0, 3.0, 0.0, 0.0];
+
+        apply_banking(&mut velocities, &accelerations, 0.1);
+
+        assert!((velocities[0] - 3.0).abs() < 1e-6);
+        assert_eq!(velocities[1], 0.0);
+        assert!(velocities[2].abs() < 1e-6);
+        assert_eq!(&velocities[3..], &[4.0, 0.0, 1.0]);
+    }
+}
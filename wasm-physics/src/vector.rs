@@ -0,0 +1,377 @@
+//! Batch vector helpers for interleaved (x, y) arrays.
+
+use wasm_bindgen::prelude::*;
+
+/// Batch normalize vectors to unit length in place.
+///
+/// Zero-length vectors are left as (0, 0).
+///
+/// # Arguments
+/// * `vectors` - Mutable array of vectors (x, y pairs)
+#[wasm_bindgen]
+pub fn normalize_all(vectors: &mut [f32]) {
+    for v in vectors.chunks_exact_mut(2) {
+        let len_sq = v[0] * v[0] + v[1] * v[1];
+        if len_sq > 0.0 {
+            let inv = 1.0 / len_sq.sqrt();
+            v[0] *= inv;
+            v[1] *= inv;
+        }
+    }
+}
+
+/// Batch compute heading angles from velocities.
+///
+/// Writes `atan2(vy, vx)` per boid; boids at rest get a stable 0.0.
+///
+/// # Arguments
+/// * `velocities` - Array of velocities (x, y pairs)
+/// * `out_angles` - Output array of angles in radians (one per boid)
+#[wasm_bindgen]
+pub fn compute_headings(velocities: &[f32], out_angles: &mut [f32]) {
+    for (v, out) in velocities.chunks_exact(2).zip(out_angles.iter_mut()) {
+        *out = if v[0] == 0.0 && v[1] == 0.0 {
+            0.0
+        } else {
+            v[1].atan2(v[0])
+        };
+    }
+}
+
+/// Batch square root in place.
+///
+/// Uses `f32x4_sqrt` four values at a time when compiled with `simd128`;
+/// results match `f32::sqrt` exactly either way.
+///
+/// # Arguments
+/// * `values` - Mutable array of values
+#[wasm_bindgen]
+pub fn sqrt_all(values: &mut [f32]) {
+    #[cfg(target_feature = "simd128")]
+    let start = if crate::simd_enabled() {
+        use core::arch::wasm32::*;
+
+        let chunks = values.len() / 4;
+        for c in 0..chunks {
+            // SAFETY: `c * 4 + 4 <= values.len()`; unaligned access is fine.
+            unsafe {
+                let ptr = values.as_mut_ptr().add(c * 4) as *mut v128;
+                v128_store(ptr, f32x4_sqrt(v128_load(ptr)));
+            }
+        }
+        chunks * 4
+    } else {
+        0
+    };
+    #[cfg(not(target_feature = "simd128"))]
+    let start = 0;
+
+    for v in &mut values[start..] {
+        *v = v.sqrt();
+    }
+}
+
+/// Batch approximate reciprocal square root in place.
+///
+/// Starts from the classic bit-level estimate and refines it with two
+/// Newton steps, for a relative error below 1e-5. Values must be positive.
+/// The SIMD and scalar paths compute the same approximation.
+///
+/// # Arguments
+/// * `values` - Mutable array of values
+#[wasm_bindgen]
+pub fn rsqrt_all(values: &mut [f32]) {
+    #[cfg(target_feature = "simd128")]
+    let start = if crate::simd_enabled() {
+        use core::arch::wasm32::*;
+
+        let magic = i32x4_splat(RSQRT_MAGIC);
+        let half = f32x4_splat(0.5);
+        let three_halves = f32x4_splat(1.5);
+
+        let chunks = values.len() / 4;
+        for c in 0..chunks {
+            // SAFETY: `c * 4 + 4 <= values.len()`; unaligned access is fine.
+            unsafe {
+                let ptr = values.as_mut_ptr().add(c * 4) as *mut v128;
+                let x = v128_load(ptr);
+                let half_x = f32x4_mul(x, half);
+                let mut y = i32x4_sub(magic, u32x4_shr(x, 1));
+                for _ in 0..2 {
+                    y = f32x4_mul(
+                        y,
+                        f32x4_sub(three_halves, f32x4_mul(half_x, f32x4_mul(y, y))),
+                    );
+                }
+                v128_store(ptr, y);
+            }
+        }
+        chunks * 4
+    } else {
+        0
+    };
+    #[cfg(not(target_feature = "simd128"))]
+    let start = 0;
+
+    for v in &mut values[start..] {
+        *v = rsqrt(*v);
+    }
+}
+
+/// Initial-estimate constant for the bit-level reciprocal square root.
+const RSQRT_MAGIC: i32 = 0x5F37_59DF;
+
+/// Scalar version of the [`rsqrt_all`] approximation.
+#[inline]
+fn rsqrt(x: f32) -> f32 {
+    let half_x = x * 0.5;
+    let mut y = f32::from_bits((RSQRT_MAGIC as u32).wrapping_sub(x.to_bits() >> 1));
+    for _ in 0..2 {
+        y *= 1.5 - half_x * y * y;
+    }
+    y
+}
+
+/// Batch dot product of paired vectors.
+///
+/// # Arguments
+/// * `a` - First vectors (x, y pairs)
+/// * `b` - Second vectors (x, y pairs)
+/// * `out` - Output `a · b` per pair
+#[wasm_bindgen]
+pub fn dot_all(a: &[f32], b: &[f32], out: &mut [f32]) {
+    for ((u, v), o) in a.chunks_exact(2).zip(b.chunks_exact(2)).zip(out.iter_mut()) {
+        *o = u[0] * v[0] + u[1] * v[1];
+    }
+}
+
+/// Batch 2D cross product (z component) of paired vectors.
+///
+/// Positive when `b` points counter-clockwise of `a`.
+///
+/// # Arguments
+/// * `a` - First vectors (x, y pairs)
+/// * `b` - Second vectors (x, y pairs)
+/// * `out` - Output `a.x * b.y - a.y * b.x` per pair
+#[wasm_bindgen]
+pub fn cross_all(a: &[f32], b: &[f32], out: &mut [f32]) {
+    for ((u, v), o) in a.chunks_exact(2).zip(b.chunks_exact(2)).zip(out.iter_mut()) {
+        *o = u[0] * v[1] - u[1] * v[0];
+    }
+}
+
+/// Batch rotate every vector by the same angle, counter-clockwise.
+///
+/// # Arguments
+/// * `vectors` - Mutable array of vectors (x, y pairs)
+/// * `angle_radians` - Rotation angle
+#[wasm_bindgen]
+pub fn rotate_all(vectors: &mut [f32], angle_radians: f32) {
+    let (sin, cos) = angle_radians.sin_cos();
+
+    for v in vectors.chunks_exact_mut(2) {
+        rotate(v, sin, cos);
+    }
+}
+
+/// Batch rotate each vector by its own angle, counter-clockwise.
+///
+/// Vectors without an angle entry are left unchanged.
+///
+/// # Arguments
+/// * `vectors` - Mutable array of vectors (x, y pairs)
+/// * `angles` - Rotation angle per vector, in radians
+#[wasm_bindgen]
+pub fn rotate_each(vectors: &mut [f32], angles: &[f32]) {
+    for (v, &angle) in vectors.chunks_exact_mut(2).zip(angles) {
+        let (sin, cos) = angle.sin_cos();
+        rotate(v, sin, cos);
+    }
+}
+
+/// Rotate one (x, y) pair given the angle's sine and cosine.
+#[inline]
+fn rotate(v: &mut [f32], sin: f32, cos: f32) {
+    let (x, y) = (v[0], v[1]);
+    v[0] = x * cos - y * sin;
+    v[1] = x * sin + y * cos;
+}
+
+/// Batch compute signed turn rates between consecutive velocities.
+///
+/// Writes the heading change from `prev_velocities` to `velocities` divided
+/// by `dt`, in radians per second; positive is counter-clockwise. Boids at
+/// rest in either frame, or a non-positive `dt`, give 0.
+///
+/// # Arguments
+/// * `velocities` - Current velocities (x, y pairs)
+/// * `prev_velocities` - Previous frame's velocities (x, y pairs)
+/// * `dt` - Time between the two frames
+/// * `out` - Output turn rate per boid
+#[wasm_bindgen]
+pub fn compute_turn_rates(velocities: &[f32], prev_velocities: &[f32], dt: f32, out: &mut [f32]) {
+    let pairs = velocities
+        .chunks_exact(2)
+        .zip(prev_velocities.chunks_exact(2));
+
+    for ((v, p), o) in pairs.zip(out.iter_mut()) {
+        *o = if dt > 0.0 {
+            let cross = p[0] * v[1] - p[1] * v[0];
+            let dot = p[0] * v[0] + p[1] * v[1];
+            cross.atan2(dot) / dt
+        } else {
+            0.0
+        };
+    }
+}
+
+/// Batch compute the speed of each boid.
+///
+/// Writes `sqrt(vx² + vy²)` per boid, four boids at a time with
+/// `f32x4_sqrt` when compiled with `simd128`. Processes as many boids as
+/// both arrays hold.
+///
+/// # Arguments
+/// * `velocities` - Array of velocities (x, y pairs)
+/// * `out` - Output speed per boid
+#[wasm_bindgen]
+pub fn compute_speeds(velocities: &[f32], out: &mut [f32]) {
+    let count = (velocities.len() / 2).min(out.len());
+
+    #[cfg(target_feature = "simd128")]
+    let start = if crate::simd_enabled() {
+        use core::arch::wasm32::*;
+
+        let chunks = count / 4;
+        for c in 0..chunks {
+            // SAFETY: boids `4c..4c + 4` lie within `count`, so the eight
+            // velocity floats and four output floats are in bounds.
+            unsafe {
+                let a = v128_load(velocities.as_ptr().add(c * 8) as *const v128);
+                let b = v128_load(velocities.as_ptr().add(c * 8 + 4) as *const v128);
+                let xs = i32x4_shuffle::<0, 2, 4, 6>(a, b);
+                let ys = i32x4_shuffle::<1, 3, 5, 7>(a, b);
+                let speed_sq = f32x4_add(f32x4_mul(xs, xs), f32x4_mul(ys, ys));
+                v128_store(
+                    out.as_mut_ptr().add(c * 4) as *mut v128,
+                    f32x4_sqrt(speed_sq),
+                );
+            }
+        }
+        chunks * 4
+    } else {
+        0
+    };
+    #[cfg(not(target_feature = "simd128"))]
+    let start = 0;
+
+    for i in start..count {
+        let vx = velocities[i * 2];
+        let vy = velocities[i * 2 + 1];
+        out[i] = (vx * vx + vy * vy).sqrt();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_all() {
+        let mut vectors = vec![3.0, 4.0, 0.0, 0.0];
+
+        normalize_all(&mut vectors);
+
+        assert!((vectors[0] - 0.6).abs() < 1e-6);
+        assert!((vectors[1] - 0.8).abs() < 1e-6);
+        assert_eq!(vectors[2..4], [0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_compute_headings() {
+        let velocities = vec![0.0, 1.0, 0.0, 0.0, -1.0, 0.0];
+        let mut angles = vec![9.0; 3];
+
+        compute_headings(&velocities, &mut angles);
+
+        assert!((angles[0] - std::f32::consts::FRAC_PI_2).abs() < 1e-6);
+        assert_eq!(angles[1], 0.0);
+        assert!((angles[2] - std::f32::consts::PI).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_sqrt_and_rsqrt_all() {
+        let original: Vec<f32> = (1..=11).map(|i| i as f32 * 0.75).collect();
+
+        let mut roots = original.clone();
+        sqrt_all(&mut roots);
+        let mut inv = original.clone();
+        rsqrt_all(&mut inv);
+
+        for ((&x, &r), &q) in original.iter().zip(&roots).zip(&inv) {
+            assert_eq!(r, x.sqrt());
+            assert!((q * x.sqrt() - 1.0).abs() < 1e-5);
+        }
+
+        let mut four = vec![4.0];
+        rsqrt_all(&mut four);
+        assert!((four[0] - 0.5).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_dot_and_cross() {
+        let a = vec![1.0, 0.0, 2.0, 3.0];
+        let b = vec![0.0, 1.0, 4.0, -1.0];
+        let mut dots = vec![9.0; 2];
+        let mut crosses = vec![9.0; 2];
+
+        dot_all(&a, &b, &mut dots);
+        cross_all(&a, &b, &mut crosses);
+
+        assert_eq!(dots, vec![0.0, 5.0]);
+        assert_eq!(crosses, vec![1.0, -14.0]);
+    }
+
+    #[test]
+    fn test_rotate_quarter_turn() {
+        let mut vectors = vec![1.0, 0.0, 0.0, 2.0];
+        rotate_all(&mut vectors, core::f32::consts::FRAC_PI_2);
+
+        assert!(vectors[0].abs() < 1e-6 && (vectors[1] - 1.0).abs() < 1e-6);
+        assert!((vectors[2] + 2.0).abs() < 1e-6 && vectors[3].abs() < 1e-6);
+
+        let mut each = vec![1.0, 0.0, 1.0, 0.0, 1.0, 0.0];
+        rotate_each(&mut each, &[core::f32::consts::FRAC_PI_2, 0.0]);
+        assert!((each[1] - 1.0).abs() < 1e-6);
+        assert_eq!(&each[2..], &[1.0, 0.0, 1.0, 0.0]);
+    }
+
+    #[test]
+    fn test_turn_rates() {
+        let prev = vec![1.0, 0.0, 1.0, 0.0, 0.0, 0.0];
+        let current = vec![1.0, 1.0, 1.0, -1.0, 1.0, 0.0];
+        let mut rates = vec![9.0; 3];
+
+        compute_turn_rates(&current, &prev, 0.5, &mut rates);
+
+        let quarter = core::f32::consts::FRAC_PI_4;
+        assert!((rates[0] - quarter / 0.5).abs() < 1e-5);
+        assert!((rates[1] + quarter / 0.5).abs() < 1e-5);
+        assert_eq!(rates[2], 0.0);
+    }
+
+    #[test]
+    fn test_compute_speeds() {
+        let mut velocities = vec![3.0, 4.0];
+        velocities.extend((0..10).map(|i| i as f32 - 4.0));
+        let mut out = vec![0.0; 6];
+
+        compute_speeds(&velocities, &mut out);
+
+        assert_eq!(out[0], 5.0);
+        for i in 1..6 {
+            let (vx, vy) = (velocities[i * 2], velocities[i * 2 + 1]);
+            assert_eq!(out[i], (vx * vx + vy * vy).sqrt());
+        }
+    }
+}